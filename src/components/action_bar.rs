@@ -0,0 +1,52 @@
+use crate::components::base_button::BaseButton;
+use crate::store::AppState;
+use crate::types::align::Align;
+use crate::types::order::ZOrder;
+use strum::IntoEnumIterator;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ActionBarProps {
+    pub position: (f64, f64),
+    pub items: usize,
+}
+
+#[function_component(ActionBar)]
+pub fn action_bar(ActionBarProps { position, items }: &ActionBarProps) -> Html {
+    let dispatch = use_dispatch::<AppState>();
+    let (left, top) = position;
+    html! {
+        <div style={format!(r#"
+            position: fixed;
+            left: {left}px;
+            top: {top}px;
+            transform: translate(0, -100%);
+            background-color: white;
+            box-shadow: 0px 7px 14px 0px rgb(142, 142, 142);
+        "#)}>
+        {{
+            Align::iter().filter(|align| align.min_items() <= *items).map(|align| html!{
+                <BaseButton
+                    title={align.button_title()}
+                    onclick={dispatch.reduce_mut_callback(move |app| {
+                        app.align_selected(align);
+                    })}>
+                    <i class={classes!("ti", align.button_icon())} />
+                </BaseButton>
+            }).collect::<Html>()
+        }}
+        {{
+            ZOrder::iter().map(|order| html!{
+                <BaseButton
+                    title={order.button_title()}
+                    onclick={dispatch.reduce_mut_callback(move |app| {
+                        app.reorder_selected(order);
+                    })}>
+                    <i class={classes!("ti", order.button_icon())} />
+                </BaseButton>
+            }).collect::<Html>()
+        }}
+        </div>
+    }
+}
@@ -0,0 +1,64 @@
+use crate::store::AppState;
+use crate::types::order::ZOrder;
+use strum::IntoEnumIterator;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ContextMenuProps {
+    pub position: (i32, i32),
+    pub onclose: Callback<()>,
+}
+
+#[function_component(ContextMenu)]
+pub fn context_menu(ContextMenuProps { position, onclose }: &ContextMenuProps) -> Html {
+    let dispatch = use_dispatch::<AppState>();
+    let (left, top) = position;
+    let style = "background-color: white; border: none; padding: 5px 10px; text-align: left; cursor: pointer;";
+    html! {
+        <div style={format!(r#"
+            position: fixed;
+            left: {left}px;
+            top: {top}px;
+            display: flex;
+            flex-direction: column;
+            background-color: white;
+            box-shadow: 0px 7px 14px 0px rgb(142, 142, 142);
+        "#)}>
+        {{
+            ZOrder::iter().map(|order| html!{
+                <button
+                    {style}
+                    onclick={
+                        let onclose = onclose.clone();
+                        dispatch.reduce_mut_callback_with(move |app, _| {
+                            app.reorder_selected(order);
+                            onclose.emit(());
+                        })
+                    }>
+                    <i class={classes!("ti", order.button_icon())} />
+                    {format!(" {}", order.button_title())}
+                </button>
+            }).collect::<Html>()
+        }}
+        {{
+            [(true, "ti-flip-vertical", "Flip horizontal"), (false, "ti-flip-horizontal", "Flip vertical")]
+                .into_iter()
+                .map(|(horizontal, icon, title)| html!{
+                    <button
+                        {style}
+                        onclick={
+                            let onclose = onclose.clone();
+                            dispatch.reduce_mut_callback_with(move |app, _| {
+                                app.flip_selected(horizontal);
+                                onclose.emit(());
+                            })
+                        }>
+                        <i class={classes!("ti", icon)} />
+                        {format!(" {title}")}
+                    </button>
+                }).collect::<Html>()
+        }}
+        </div>
+    }
+}
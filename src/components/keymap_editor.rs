@@ -0,0 +1,105 @@
+use crate::components::base_button::BaseButton;
+use crate::store::library::Library;
+use crate::types::events::Modifiers;
+use crate::types::keymap::{Command, KeyChord};
+use strum::IntoEnumIterator;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct KeymapEditorProps {
+    pub onclose: Callback<MouseEvent>,
+}
+
+#[function_component(KeymapEditor)]
+pub fn keymap_editor(KeymapEditorProps { onclose }: &KeymapEditorProps) -> Html {
+    let library = use_dispatch::<Library>();
+    let keymap = use_selector(|library: &Library| library.get_keymap().clone());
+    let capturing = use_state(|| None::<Command>);
+    let onkeydown = {
+        let capturing = capturing.clone();
+        library.reduce_mut_callback_with(move |library, event: KeyboardEvent| {
+            let Some(command) = *capturing else {
+                return;
+            };
+            event.prevent_default();
+            let modifiers = Modifiers {
+                ctrl: event.ctrl_key() || event.meta_key(),
+                shift: event.shift_key(),
+                alt: event.alt_key(),
+            };
+            let key = match event.code().strip_prefix("Key") {
+                Some(letter) if modifiers.alt => letter.to_string(),
+                _ => event.key(),
+            };
+            let chord = KeyChord::new(&key, modifiers);
+            if chord.is_modifier() {
+                return;
+            }
+            if key != "Escape" || modifiers != Modifiers::default() {
+                library.modify_keymap(|keymap| keymap.set_binding(command, chord.clone()));
+            }
+            capturing.set(None);
+        })
+    };
+    html! {
+        <div
+            tabindex="0"
+            {onkeydown}
+            style=r#"
+                position: fixed;
+                left: 50%;
+                top: 50%;
+                transform: translate(-50%, -50%);
+                max-height: 80%;
+                overflow-y: auto;
+                padding: 10px;
+                background-color: white;
+                box-shadow: 0px 7px 14px 0px rgb(142, 142, 142);
+            "#>
+            <div style="display: flex; align-items: center;">
+                <b style="flex: 1;">{"Keyboard shortcuts"}</b>
+                <BaseButton title="Close" onclick={onclose.clone()}>
+                    <i class={classes!("ti", "ti-x")} />
+                </BaseButton>
+            </div>
+            <table>
+            {{
+                Command::iter().map(|command| {
+                    let chord = if *capturing == Some(command) {
+                        "Press keys, Escape to cancel".to_string()
+                    } else {
+                        keymap.binding(&command).map_or("-".into(), |chord| chord.to_string())
+                    };
+                    html!{
+                        <tr>
+                            <td>{command.to_string()}</td>
+                            <td>
+                                <button
+                                    title="Change shortcut"
+                                    onclick={
+                                        let capturing = capturing.clone();
+                                        Callback::from(move |_| capturing.set(Some(command)))
+                                    }>
+                                    {chord}
+                                </button>
+                            </td>
+                            <td>
+                            if keymap.is_overridden(&command) {
+                                <BaseButton
+                                    title="Reset to default"
+                                    onclick={library.reduce_mut_callback(move |library| {
+                                        library.modify_keymap(|keymap| keymap.reset_binding(&command));
+                                    })}>
+                                    <i class={classes!("ti", "ti-restore")} />
+                                </BaseButton>
+                            }
+                            </td>
+                        </tr>
+                    }
+                }).collect::<Html>()
+            }}
+            </table>
+        </div>
+    }
+}
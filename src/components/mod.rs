@@ -1,3 +1,7 @@
+pub mod action_bar;
 pub mod base_button;
 pub mod bg_color_button;
 pub mod color_button;
+pub mod context_menu;
+pub mod keymap_editor;
+pub mod preset_editor;
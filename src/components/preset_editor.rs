@@ -0,0 +1,184 @@
+use crate::components::base_button::BaseButton;
+use crate::store::AppState;
+use crate::store::library::Library;
+use crate::types::presets::ShapeStyle;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct PresetEditorProps {
+    pub onclose: Callback<MouseEvent>,
+}
+
+/// Stroke and fill colors of the style.
+fn swatch(style: &ShapeStyle) -> Html {
+    let fill = style
+        .bg_color
+        .as_ref()
+        .map_or("white".to_string(), |bg_color| bg_color.to_string());
+    html! {
+        <span style="font-size: 20px;">
+            <i style={format!("color: {}", style.color)} class={classes!("ti", "ti-square")} />
+            <i style={format!("color: {fill}")} class={classes!("ti", "ti-square-filled")} />
+        </span>
+    }
+}
+
+#[function_component(PresetEditor)]
+pub fn preset_editor(PresetEditorProps { onclose }: &PresetEditorProps) -> Html {
+    let dispatch = use_dispatch::<AppState>();
+    let library = use_dispatch::<Library>();
+    let presets = use_selector(|app: &AppState| app.get_presets().clone());
+    let current = use_selector(|app: &AppState| app.get_preset().cloned());
+    let library_presets = use_selector(|library: &Library| library.get_presets().clone());
+    let name = use_state(String::new);
+    let restyle = use_state(|| true);
+    let onchange = {
+        let name = name.clone();
+        Callback::from(move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            name.set(input.value().trim().to_string());
+        })
+    };
+    let save = {
+        let (name, restyle) = (name.clone(), restyle.clone());
+        dispatch.reduce_mut_callback(move |app| {
+            if !name.is_empty() {
+                app.save_preset(&name, *restyle);
+            }
+        })
+    };
+    let toggle_restyle = {
+        let restyle = restyle.clone();
+        Callback::from(move |_| restyle.set(!*restyle))
+    };
+    html! {
+        <div style=r#"
+                position: fixed;
+                left: 50%;
+                top: 50%;
+                transform: translate(-50%, -50%);
+                max-height: 80%;
+                overflow-y: auto;
+                padding: 10px;
+                background-color: white;
+                box-shadow: 0px 7px 14px 0px rgb(142, 142, 142);
+            "#>
+            <div style="display: flex; align-items: center;">
+                <b style="flex: 1;">{"Style presets"}</b>
+                <BaseButton title="Close" onclick={onclose.clone()}>
+                    <i class={classes!("ti", "ti-x")} />
+                </BaseButton>
+            </div>
+            <div style="display: flex; align-items: center;">
+                <input
+                    type="text"
+                    placeholder="Preset name"
+                    value={(*name).clone()}
+                    {onchange}
+                />
+                <BaseButton
+                    title="Save the current style as the preset, replacing any with the name"
+                    onclick={save}>
+                    <i class={classes!("ti", "ti-device-floppy")} />
+                </BaseButton>
+                <BaseButton
+                    selected={*restyle}
+                    title="Restyle the shapes linked to a preset when it is saved again"
+                    onclick={toggle_restyle}>
+                    <i class={classes!("ti", "ti-link")} />
+                </BaseButton>
+            </div>
+            <i>{"Document"}</i>
+            <table>
+            {{
+                presets.iter().map(|(preset, style)| {
+                    let preset = preset.clone();
+                    html!{
+                        <tr>
+                            <td>{swatch(style)}</td>
+                            <td>
+                            if current.as_deref() == Some(preset.as_str()) {
+                                <b>{&preset}</b>
+                            } else {
+                                {&preset}
+                            }
+                            </td>
+                            <td>
+                                <BaseButton
+                                    title="Apply to the selection and to new shapes"
+                                    onclick={dispatch.reduce_mut_callback({
+                                        let preset = preset.clone();
+                                        move |app| app.apply_preset(&preset)
+                                    })}>
+                                    <i class={classes!("ti", "ti-brush")} />
+                                </BaseButton>
+                                <BaseButton
+                                    title="Update from the current style"
+                                    onclick={dispatch.reduce_mut_callback({
+                                        let (preset, restyle) = (preset.clone(), restyle.clone());
+                                        move |app| app.save_preset(&preset, *restyle)
+                                    })}>
+                                    <i class={classes!("ti", "ti-refresh")} />
+                                </BaseButton>
+                                <BaseButton
+                                    title="Save to my library"
+                                    onclick={library.reduce_mut_callback({
+                                        let (preset, style) = (preset.clone(), style.clone());
+                                        move |library| library.add_preset(&preset, style.clone())
+                                    })}>
+                                    <i class={classes!("ti", "ti-books")} />
+                                </BaseButton>
+                                <BaseButton
+                                    title="Remove, linked shapes keep their style"
+                                    onclick={dispatch.reduce_mut_callback({
+                                        let preset = preset.clone();
+                                        move |app| app.remove_preset(&preset)
+                                    })}>
+                                    <i class={classes!("ti", "ti-trash")} />
+                                </BaseButton>
+                            </td>
+                        </tr>
+                    }
+                }).collect::<Html>()
+            }}
+            </table>
+            <i>{"My library"}</i>
+            <table>
+            {{
+                library_presets.iter().map(|(preset, style)| {
+                    let preset = preset.clone();
+                    html!{
+                        <tr>
+                            <td>{swatch(style)}</td>
+                            <td>{&preset}</td>
+                            <td>
+                                <BaseButton
+                                    title="Add to the document and apply"
+                                    onclick={dispatch.reduce_mut_callback({
+                                        let (preset, style) = (preset.clone(), style.clone());
+                                        move |app| {
+                                            app.add_preset(&preset, style.clone());
+                                            app.apply_preset(&preset);
+                                        }
+                                    })}>
+                                    <i class={classes!("ti", "ti-file-import")} />
+                                </BaseButton>
+                                <BaseButton
+                                    title="Remove from my library"
+                                    onclick={library.reduce_mut_callback({
+                                        let preset = preset.clone();
+                                        move |library| library.remove_preset(&preset)
+                                    })}>
+                                    <i class={classes!("ti", "ti-trash")} />
+                                </BaseButton>
+                            </td>
+                        </tr>
+                    }
+                }).collect::<Html>()
+            }}
+            </table>
+        </div>
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use yewdux::prelude::*;
+
+use crate::types::keymap::Keymap;
+use crate::types::presets::{Presets, ShapeStyle};
+
+/// Preferences of the user kept apart from the document, to be reused across documents.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize, Serialize, Store)]
+#[store(storage = "local", storage_tab_sync)]
+pub struct Library {
+    presets: Presets,
+    /// keyboard shortcuts changed by the user
+    #[serde(default)]
+    keymap: Keymap,
+}
+
+impl Library {
+    pub fn get_presets(&self) -> &Presets {
+        &self.presets
+    }
+
+    pub fn add_preset(&mut self, name: &str, style: ShapeStyle) {
+        self.presets.insert(name.to_string(), style);
+    }
+
+    pub fn remove_preset(&mut self, name: &str) {
+        self.presets.remove(name);
+    }
+
+    pub fn get_keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    pub fn modify_keymap(&mut self, modification: impl Fn(&mut Keymap)) {
+        modification(&mut self.keymap);
+    }
+}
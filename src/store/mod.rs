@@ -1,15 +1,45 @@
+use std::rc::Rc;
+
 use serde::{Deserialize, Serialize};
 use yewdux::prelude::*;
 
 use crate::types::{
+    align::Align,
     colors::{BackgroundColor, Color},
+    effects::Effects,
+    events::{Modifiers, Point},
+    fills::FillStyle,
+    grid::Grid,
+    guides::Guides,
     ids::Id,
-    shapes::Shape,
-    tools::Tool,
+    keymap::Command,
+    order::ZOrder,
+    presets::{Presets, ShapeStyle},
+    shapes::{BBox, Shape, ShapeType},
+    sketch::RenderStyle,
+    strokes::{Dash, StrokeWidth},
+    tools::{
+        Tool,
+        connector_tool::Connect,
+        connector_tool::ConnectorOptions,
+        erase_tool::Erase,
+        erase_tool::EraseOptions,
+        eyedropper_tool::Eyedropper,
+        pen_tool::Pen,
+        pencil_tool::Pencil,
+        select_tool::{Select, SelectOptions},
+        shape_tool::{
+            CornerOptions, DiamondShape, EllipseShape, PolygonOptions, PolygonShape,
+            RectangleShape, StarShape, TriangleShape,
+        },
+    },
 };
 
-use self::shapes::Shapes;
+use crate::utils::rotate_point;
+
+use self::shapes::{Group, Shapes};
 
+pub mod library;
 pub mod shapes;
 pub mod tools;
 
@@ -21,6 +51,38 @@ pub struct AppState {
     pointer: String,
     color: Color,
     bg_color: Option<BackgroundColor>,
+    #[serde(default)]
+    grid: Grid,
+    #[serde(default)]
+    select_options: SelectOptions,
+    #[serde(default)]
+    erase_options: EraseOptions,
+    #[serde(default)]
+    polygon_options: PolygonOptions,
+    #[serde(default)]
+    corner_options: CornerOptions,
+    #[serde(default)]
+    connector_options: ConnectorOptions,
+    /// style of the new shapes
+    #[serde(default)]
+    render_style: RenderStyle,
+    /// outline of the new shapes
+    #[serde(default)]
+    stroke_width: StrokeWidth,
+    #[serde(default)]
+    dash: Dash,
+    /// fill of the new shapes
+    #[serde(default)]
+    fill_style: FillStyle,
+    /// effects of the new shapes
+    #[serde(default)]
+    effects: Effects,
+    /// preset the new shapes are linked to, until their style is changed
+    #[serde(default)]
+    preset: Option<String>,
+    /// style copied from a shape, to be pasted onto others
+    #[serde(skip)]
+    copied_style: Option<ShapeStyle>,
 }
 
 impl AppState {
@@ -41,16 +103,254 @@ impl AppState {
 
     pub fn set_color(&mut self, color: Color) {
         self.color = color.clone();
+        self.preset = None;
     }
 
     pub fn set_bg_color(&mut self, bg_color: Option<BackgroundColor>) {
         self.bg_color = bg_color.clone();
+        self.preset = None;
     }
 
     pub fn get_bg_color(&self) -> &Option<BackgroundColor> {
         &self.bg_color
     }
 
+    pub fn get_grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn modify_grid(&mut self, modification: impl Fn(&mut Grid)) {
+        modification(&mut self.grid);
+    }
+
+    /// Snaps the point to the grid unless snapping is off or alt is held.
+    pub fn snap(&self, point: &Point, modifiers: &Modifiers) -> Point {
+        if self.grid.snap && !modifiers.alt {
+            self.grid.snap_point(point)
+        } else {
+            *point
+        }
+    }
+
+    /// Snaps the box to the grid and then to guides of shapes other than `exclude`.
+    pub fn snap_bbox(&self, bbox: &BBox, exclude: &[Id], modifiers: &Modifiers) -> (BBox, Guides) {
+        if modifiers.alt {
+            return (bbox.clone(), Guides::default());
+        }
+        let (left, top) = self.snap(&(bbox.left, bbox.top), modifiers);
+        let bbox = bbox.translate(left - bbox.left, top - bbox.top);
+        Guides::snap_bbox(&bbox, &self.shapes.other_bboxes(exclude))
+    }
+
+    /// Snaps the point to the grid and then to guides of shapes other than `exclude`.
+    pub fn snap_point(
+        &self,
+        point: &Point,
+        exclude: &[Id],
+        modifiers: &Modifiers,
+    ) -> (Point, Guides) {
+        if modifiers.alt {
+            return (*point, Guides::default());
+        }
+        Guides::snap_point(
+            &self.snap(point, modifiers),
+            &self.shapes.other_bboxes(exclude),
+        )
+    }
+
+    pub fn get_select_options(&self) -> &SelectOptions {
+        &self.select_options
+    }
+
+    pub fn modify_select_options(&mut self, modification: impl Fn(&mut SelectOptions)) {
+        modification(&mut self.select_options);
+    }
+
+    pub fn get_erase_options(&self) -> &EraseOptions {
+        &self.erase_options
+    }
+
+    pub fn modify_erase_options(&mut self, modification: impl Fn(&mut EraseOptions)) {
+        modification(&mut self.erase_options);
+    }
+
+    pub fn get_polygon_options(&self) -> &PolygonOptions {
+        &self.polygon_options
+    }
+
+    pub fn modify_polygon_options(&mut self, modification: impl Fn(&mut PolygonOptions)) {
+        modification(&mut self.polygon_options);
+    }
+
+    pub fn get_corner_options(&self) -> &CornerOptions {
+        &self.corner_options
+    }
+
+    /// Changes the corner options and applies them to the selected rectangles.
+    pub fn modify_corner_options(&mut self, modification: impl Fn(&mut CornerOptions)) {
+        modification(&mut self.corner_options);
+        let corner_radius = self.corner_options.corner_radius();
+        self.modify_selected(|shape| {
+            if matches!(shape.get_shape_type(), ShapeType::Rectangle) {
+                shape.set_corner_radius(corner_radius.clone());
+            }
+        });
+    }
+
+    pub fn get_connector_options(&self) -> &ConnectorOptions {
+        &self.connector_options
+    }
+
+    /// Changes the connector options and applies them to the selected connectors.
+    pub fn modify_connector_options(&mut self, modification: impl Fn(&mut ConnectorOptions)) {
+        modification(&mut self.connector_options);
+        let routing = self.connector_options.routing;
+        self.modify_selected(|shape| shape.set_routing(routing));
+    }
+
+    /// Style of the single selected shape, or else of the new shapes.
+    pub fn get_render_style(&self) -> RenderStyle {
+        self.shapes
+            .single_selected()
+            .map_or(self.render_style, |shape| shape.get_style())
+    }
+
+    /// Changes the style of the new shapes and of the selected ones.
+    pub fn set_render_style(&mut self, style: RenderStyle) {
+        self.render_style = style;
+        self.preset = None;
+        self.modify_selected(|shape| shape.set_style(style));
+    }
+
+    /// Stroke width of the single selected shape, or else of the new shapes.
+    pub fn get_stroke_width(&self) -> StrokeWidth {
+        self.shapes
+            .single_selected()
+            .map_or(self.stroke_width, |shape| shape.get_stroke_width())
+    }
+
+    /// Changes the stroke width of the new shapes and of the selected ones.
+    pub fn set_stroke_width(&mut self, stroke_width: StrokeWidth) {
+        self.stroke_width = stroke_width;
+        self.preset = None;
+        self.modify_selected(|shape| shape.set_stroke_width(stroke_width));
+    }
+
+    /// Dash pattern of the single selected shape, or else of the new shapes.
+    pub fn get_dash(&self) -> Dash {
+        self.shapes
+            .single_selected()
+            .map_or(self.dash, |shape| shape.get_dash())
+    }
+
+    /// Changes the dash pattern of the new shapes and of the selected ones.
+    pub fn set_dash(&mut self, dash: Dash) {
+        self.dash = dash;
+        self.preset = None;
+        self.modify_selected(|shape| shape.set_dash(dash));
+    }
+
+    /// Fill of the single selected shape, or else of the new shapes.
+    pub fn get_fill_style(&self) -> &FillStyle {
+        self.shapes
+            .single_selected()
+            .map_or(&self.fill_style, |shape| shape.get_fill())
+    }
+
+    /// Changes the fill of the new shapes and, each on its own, of the selected ones.
+    pub fn modify_fill_style(&mut self, modification: impl Fn(&mut FillStyle)) {
+        modification(&mut self.fill_style);
+        self.preset = None;
+        self.modify_selected(|shape| shape.modify_fill(&modification));
+    }
+
+    /// Effects of the single selected shape, or else of the new shapes.
+    pub fn get_effects(&self) -> &Effects {
+        self.shapes
+            .single_selected()
+            .map_or(&self.effects, |shape| shape.get_effects())
+    }
+
+    /// Changes the effects of the new shapes and, each on its own, of the selected ones.
+    pub fn modify_effects(&mut self, modification: impl Fn(&mut Effects)) {
+        modification(&mut self.effects);
+        self.preset = None;
+        self.modify_selected(|shape| shape.modify_effects(&modification));
+    }
+
+    /// Style of the new shapes.
+    pub fn get_shape_style(&self) -> ShapeStyle {
+        ShapeStyle {
+            color: self.color.clone(),
+            bg_color: self.bg_color.clone(),
+            fill: self.fill_style.clone(),
+            render_style: self.render_style,
+            stroke_width: self.stroke_width,
+            dash: self.dash,
+            effects: self.effects.clone(),
+        }
+    }
+
+    pub fn get_presets(&self) -> &Presets {
+        &self.shapes.presets
+    }
+
+    pub fn get_preset(&self) -> Option<&String> {
+        self.preset.as_ref()
+    }
+
+    /// Saves the style of the new shapes as the preset, replacing any with the name.
+    ///
+    /// With `restyle` the shapes linked to the preset take the new style.
+    pub fn save_preset(&mut self, name: &str, restyle: bool) {
+        let style = self.get_shape_style();
+        self.add_preset(name, style.clone());
+        self.preset = Some(name.to_string());
+        if restyle {
+            self.shapes
+                .shapes
+                .iter_mut()
+                .filter(|shape| shape.get_preset().is_some_and(|preset| preset == name))
+                .for_each(|shape| shape.link_preset(name, &style));
+        }
+        self.shapes.version.increment();
+    }
+
+    pub fn add_preset(&mut self, name: &str, style: ShapeStyle) {
+        self.shapes.presets.insert(name.to_string(), style);
+        self.shapes.version.increment();
+    }
+
+    /// Gives the style of the preset to the new shapes and to the selected ones.
+    pub fn apply_preset(&mut self, name: &str) {
+        let Some(style) = self.shapes.presets.get(name).cloned() else {
+            return;
+        };
+        self.color = style.color.clone();
+        self.bg_color = style.bg_color.clone();
+        self.fill_style = style.fill.clone();
+        self.render_style = style.render_style;
+        self.stroke_width = style.stroke_width;
+        self.dash = style.dash;
+        self.effects = style.effects.clone();
+        self.preset = Some(name.to_string());
+        self.modify_selected(|shape| shape.link_preset(name, &style));
+    }
+
+    /// Removes the preset, the shapes linked to it keep their style.
+    pub fn remove_preset(&mut self, name: &str) {
+        self.shapes.presets.remove(name);
+        if self.preset.as_deref() == Some(name) {
+            self.preset = None;
+        }
+        self.shapes
+            .shapes
+            .iter_mut()
+            .filter(|shape| shape.get_preset().is_some_and(|preset| preset == name))
+            .for_each(|shape| shape.unlink_preset());
+        self.shapes.version.increment();
+    }
+
     pub fn set_tool(&mut self, tool: Tool) {
         self.tools.tool = tool
     }
@@ -75,14 +375,56 @@ impl AppState {
             .drain(..)
             .filter(|x| !shapes.contains(x.get_id()))
             .collect();
+        self.shapes.prune_groups();
+        self.shapes.prune_images();
+        self.shapes.update_connectors();
         self.shapes.version.increment();
     }
 
-    pub fn add_shape(&mut self, shape: Shape) {
+    /// Adds the shape drawn in the current render style.
+    pub fn add_shape(&mut self, mut shape: Shape) {
+        shape.set_style(self.render_style);
+        shape.set_stroke_width(self.stroke_width);
+        shape.set_dash(self.dash);
+        shape.set_fill(self.fill_style.clone());
+        shape.set_effects(self.effects.clone());
+        if let Some(name) = &self.preset {
+            shape.link_preset(name, &self.get_shape_style());
+        }
         self.shapes.shapes.push(shape);
         self.shapes.version.increment();
     }
 
+    /// Adds an image and selects it, the picture is stored once however many times it is added.
+    pub fn add_image(&mut self, hash: String, data: Rc<str>, bbox: &BBox) {
+        self.shapes.images.entry(hash.clone()).or_insert(data);
+        let shape = Shape::new(bbox, ShapeType::Image { hash }, self.color.clone(), None);
+        self.replace_selected(vec![shape.get_id().clone()]);
+        self.add_shape(shape);
+        self.set_tool(Select::default().into());
+    }
+
+    /// Puts the pieces in place of the shape, in its draw order position and group.
+    pub fn replace_shape(&mut self, id: &Id, pieces: Vec<Shape>) {
+        let Some(index) = self.shapes.shapes.iter().position(|x| x.get_id().eq(id)) else {
+            return;
+        };
+        let ids: Vec<Id> = pieces.iter().map(|x| x.get_id().clone()).collect();
+        self.shapes.shapes.splice(index..=index, pieces);
+        for group in self.shapes.groups.iter_mut() {
+            if let Some(index) = group.members.iter().position(|member| member.eq(id)) {
+                group.members.splice(index..=index, ids.iter().cloned());
+            }
+        }
+        self.shapes
+            .selected_shapes
+            .retain(|selected| selected.ne(id));
+        self.shapes.prune_groups();
+        self.shapes.prune_images();
+        self.shapes.update_connectors();
+        self.shapes.version.increment();
+    }
+
     pub fn modify_selected(&mut self, modification: impl Fn(&mut Shape)) {
         let shapes = self.shapes.selected_shapes.to_vec();
         self.shapes
@@ -90,6 +432,363 @@ impl AppState {
             .iter_mut()
             .filter(|x| shapes.contains(x.get_id()))
             .for_each(modification);
+        self.shapes.update_connectors();
+        self.shapes.version.increment();
+    }
+
+    /// Keeps the style of the first selected shape for pasting.
+    pub fn copy_style(&mut self) -> bool {
+        let Some(id) = self.shapes.selected_shapes.first() else {
+            return false;
+        };
+        let Some(shape) = self.shapes.shapes.iter().find(|x| x.get_id().eq(id)) else {
+            return false;
+        };
+        self.copied_style = Some(shape.get_shape_style());
+        true
+    }
+
+    /// Gives the copied style to the selected shapes, leaving their geometry alone.
+    pub fn paste_style(&mut self) -> bool {
+        let Some(style) = self.copied_style.clone() else {
+            return false;
+        };
+        if self.shapes.selected_shapes.is_empty() {
+            return false;
+        }
+        self.modify_selected(|shape| {
+            shape.set_shape_style(&style);
+            shape.unlink_preset();
+        });
+        true
+    }
+
+    pub fn group_selected(&mut self) -> bool {
+        let members = self.shapes.units(&self.shapes.selected_shapes, None);
+        if members.len() < 2 {
+            return false;
+        }
+        self.shapes.groups.push(Group {
+            id: Id::default(),
+            members,
+        });
+        self.shapes.version.increment();
+        true
+    }
+
+    pub fn ungroup_selected(&mut self) -> bool {
+        let units = self.shapes.units(&self.shapes.selected_shapes, None);
+        let before = self.shapes.groups.len();
+        self.shapes
+            .groups
+            .retain(|group| !units.contains(&group.id));
+        if before == self.shapes.groups.len() {
+            return false;
+        }
+        self.shapes.version.increment();
+        true
+    }
+
+    /// Moves selected shapes in draw order, keeping their relative order.
+    pub fn reorder_selected(&mut self, order: ZOrder) -> bool {
+        let selected = &self.shapes.selected_shapes;
+        let shapes = &mut self.shapes.shapes;
+        let before: Vec<Id> = shapes.iter().map(|x| x.get_id().clone()).collect();
+        match order {
+            ZOrder::Front => shapes.sort_by_key(|x| selected.contains(x.get_id())),
+            ZOrder::Back => shapes.sort_by_key(|x| !selected.contains(x.get_id())),
+            ZOrder::Forward => {
+                for index in (0..shapes.len().saturating_sub(1)).rev() {
+                    if selected.contains(shapes[index].get_id())
+                        && !selected.contains(shapes[index + 1].get_id())
+                    {
+                        shapes.swap(index, index + 1);
+                    }
+                }
+            }
+            ZOrder::Backward => {
+                for index in 1..shapes.len() {
+                    if selected.contains(shapes[index].get_id())
+                        && !selected.contains(shapes[index - 1].get_id())
+                    {
+                        shapes.swap(index, index - 1);
+                    }
+                }
+            }
+        }
+        if shapes.iter().map(|x| x.get_id()).eq(before.iter()) {
+            return false;
+        }
+        self.shapes.version.increment();
+        true
+    }
+
+    /// Moves the shape or group to the draw order position of `target`, a shape or group too.
+    ///
+    /// The shapes of a moved group stay together, above the target when moved up.
+    pub fn move_shape(&mut self, id: &Id, target: &Id) {
+        let moved = self.shapes.leaves(id);
+        let targets = self.shapes.leaves(target);
+        if moved.iter().any(|leaf| targets.contains(leaf)) {
+            return;
+        }
+        let shapes = &mut self.shapes.shapes;
+        let position =
+            |ids: &[Id], shapes: &[Shape]| shapes.iter().position(|x| ids.contains(x.get_id()));
+        let (Some(from), Some(to)) = (position(&moved, shapes), position(&targets, shapes)) else {
+            return;
+        };
+        let (taken, mut kept): (Vec<Shape>, Vec<Shape>) = std::mem::take(shapes)
+            .into_iter()
+            .partition(|x| moved.contains(x.get_id()));
+        let index = match from < to {
+            true => kept
+                .iter()
+                .rposition(|x| targets.contains(x.get_id()))
+                .map(|x| x + 1),
+            false => kept.iter().position(|x| targets.contains(x.get_id())),
+        };
+        let index = index.unwrap_or(kept.len());
+        kept.splice(index..index, taken);
+        *shapes = kept;
+        self.shapes.version.increment();
+    }
+
+    /// Modifies the shape, or every shape of the group.
+    pub fn modify_unit(&mut self, id: &Id, modification: impl Fn(&mut Shape)) {
+        let leaves = self.shapes.leaves(id);
+        self.shapes
+            .shapes
+            .iter_mut()
+            .filter(|x| leaves.contains(x.get_id()))
+            .for_each(modification);
+        self.shapes.update_connectors();
+        self.shapes.version.increment();
+    }
+
+    pub fn modify_shape(&mut self, id: &Id, modification: impl Fn(&mut Shape)) {
+        self.shapes
+            .shapes
+            .iter_mut()
+            .filter(|x| x.get_id().eq(id))
+            .for_each(modification);
+        self.shapes.update_connectors();
+        self.shapes.version.increment();
+    }
+
+    /// Maps the shapes' `original` boxes from the `from` box onto the `to` box.
+    pub fn transform_shapes(&mut self, originals: &[(Id, BBox)], from: &BBox, to: &BBox) {
+        for shape in self.shapes.shapes.iter_mut() {
+            if let Some((_, original)) = originals.iter().find(|(id, _)| id.eq(shape.get_id())) {
+                shape.resize_to_bbox(&original.map(from, to));
+            }
+        }
+        self.shapes.update_connectors();
+        self.shapes.version.increment();
+    }
+
+    /// Aligns or distributes the selected shapes and groups within their common box.
+    pub fn align_selected(&mut self, align: Align) -> bool {
+        let mut units: Vec<(Vec<Id>, BBox)> = self
+            .shapes
+            .selected_units()
+            .iter()
+            .map(|unit| self.shapes.leaves(unit))
+            .filter_map(|leaves| Some((leaves.clone(), self.shapes.bbox_of(&leaves)?)))
+            .collect();
+        if units.len() < align.min_items() {
+            return false;
+        }
+        let mut group = units[0].1.clone();
+        for (_, bbox) in &units[1..] {
+            group.add_bbox(bbox);
+        }
+        let mut moves: Vec<(Vec<Id>, f64, f64)> = vec![];
+        match align {
+            Align::DistributeHorizontally => {
+                units.sort_by(|(_, a), (_, b)| a.left.total_cmp(&b.left));
+                let used: f64 = units.iter().map(|(_, bbox)| bbox.width).sum();
+                let gap = (group.width - used) / (units.len() - 1) as f64;
+                let mut left = group.left;
+                for (leaves, bbox) in &units {
+                    moves.push((leaves.clone(), left - bbox.left, 0.0));
+                    left += bbox.width + gap;
+                }
+            }
+            Align::DistributeVertically => {
+                units.sort_by(|(_, a), (_, b)| a.top.total_cmp(&b.top));
+                let used: f64 = units.iter().map(|(_, bbox)| bbox.height).sum();
+                let gap = (group.height - used) / (units.len() - 1) as f64;
+                let mut top = group.top;
+                for (leaves, bbox) in &units {
+                    moves.push((leaves.clone(), 0.0, top - bbox.top));
+                    top += bbox.height + gap;
+                }
+            }
+            _ => {
+                for (leaves, bbox) in &units {
+                    let (dx, dy) = match align {
+                        Align::Left => (group.left - bbox.left, 0.0),
+                        Align::Center => (
+                            (group.width - bbox.width) / 2.0 + group.left - bbox.left,
+                            0.0,
+                        ),
+                        Align::Right => (group.right() - bbox.right(), 0.0),
+                        Align::Top => (0.0, group.top - bbox.top),
+                        Align::Middle => (
+                            0.0,
+                            (group.height - bbox.height) / 2.0 + group.top - bbox.top,
+                        ),
+                        _ => (0.0, group.bottom() - bbox.bottom()),
+                    };
+                    moves.push((leaves.clone(), dx, dy));
+                }
+            }
+        }
+        let mut changed = false;
+        for shape in self.shapes.shapes.iter_mut() {
+            if let Some((_, dx, dy)) = moves
+                .iter()
+                .find(|(leaves, _, _)| leaves.contains(shape.get_id()))
+            {
+                changed |= shape.resize_to_bbox(&shape.frame().translate(*dx, *dy));
+            }
+        }
+        self.shapes.update_connectors();
+        self.shapes.version.increment();
+        changed
+    }
+
+    /// Turns the shapes' `original` frames and rotations by `angle` around `center`.
+    pub fn rotate_shapes(&mut self, originals: &[(Id, BBox, f64)], center: &Point, angle: f64) {
+        for shape in self.shapes.shapes.iter_mut() {
+            if let Some((_, frame, rotation)) =
+                originals.iter().find(|(id, _, _)| id.eq(shape.get_id()))
+            {
+                let (x, y) = frame.center();
+                let (new_x, new_y) = rotate_point(&(x, y), center, angle);
+                let rotation = (rotation + angle).rem_euclid(std::f64::consts::TAU);
+                shape.rotate_to(&frame.translate(new_x - x, new_y - y), rotation);
+            }
+        }
+        self.shapes.update_connectors();
+        self.shapes.version.increment();
+    }
+
+    /// Mirrors the selected shapes about the center of the selection.
+    pub fn flip_selected(&mut self, horizontal: bool) -> bool {
+        let selected = &self.shapes.selected_shapes;
+        let Some(bbox) = self.shapes.bbox_of(selected) else {
+            return false;
+        };
+        let center = bbox.center();
+        self.shapes
+            .shapes
+            .iter_mut()
+            .filter(|x| x.is_interactive() && selected.contains(x.get_id()))
+            .for_each(|x| x.flip(horizontal, &center));
+        self.shapes.update_connectors();
+        self.shapes.version.increment();
+        true
+    }
+
+    pub fn select_all(&mut self) -> bool {
+        let all: Vec<Id> = self
+            .shapes
+            .shapes
+            .iter()
+            .filter(|x| x.is_interactive())
+            .map(|x| x.get_id().clone())
+            .collect();
+        let changed = all.ne(&self.shapes.selected_shapes);
+        self.replace_selected(all);
+        changed
+    }
+
+    pub fn nudge_selected(&mut self, dx: f64, dy: f64) -> bool {
+        let selected = &self.shapes.selected_shapes;
+        let mut changed = false;
+        for shape in self.shapes.shapes.iter_mut() {
+            if shape.is_interactive() && selected.contains(shape.get_id()) {
+                changed |= shape.resize_to_bbox(&shape.frame().translate(dx, dy));
+            }
+        }
+        self.shapes.update_connectors();
+        self.shapes.version.increment();
+        changed
+    }
+
+    /// Runs a command bound in the keymap, returns whether anything changed.
+    pub fn run_command(&mut self, command: Command) -> bool {
+        let (near, far) = (Command::NUDGE, Command::NUDGE_FAR);
+        match command {
+            Command::SelectTool => self.switch_tool(Select::default()),
+            Command::RectangleTool => self.switch_tool(RectangleShape::default()),
+            Command::EllipseTool => self.switch_tool(EllipseShape::default()),
+            Command::TriangleTool => self.switch_tool(TriangleShape::default()),
+            Command::DiamondTool => self.switch_tool(DiamondShape::default()),
+            Command::PolygonTool => self.switch_tool(PolygonShape::default()),
+            Command::StarTool => self.switch_tool(StarShape::default()),
+            Command::ConnectorTool => self.switch_tool(Connect::default()),
+            Command::PenTool => self.switch_tool(Pen::default()),
+            Command::PencilTool => self.switch_tool(Pencil::default()),
+            Command::EraseTool => self.switch_tool(Erase::default()),
+            Command::EyedropperTool => self.switch_tool(Eyedropper),
+            Command::NudgeLeft => self.nudge_selected(-near, 0.0),
+            Command::NudgeRight => self.nudge_selected(near, 0.0),
+            Command::NudgeUp => self.nudge_selected(0.0, -near),
+            Command::NudgeDown => self.nudge_selected(0.0, near),
+            Command::NudgeLeftFar => self.nudge_selected(-far, 0.0),
+            Command::NudgeRightFar => self.nudge_selected(far, 0.0),
+            Command::NudgeUpFar => self.nudge_selected(0.0, -far),
+            Command::NudgeDownFar => self.nudge_selected(0.0, far),
+            Command::BringToFront => self.reorder_selected(ZOrder::Front),
+            Command::BringForward => self.reorder_selected(ZOrder::Forward),
+            Command::SendBackward => self.reorder_selected(ZOrder::Backward),
+            Command::SendToBack => self.reorder_selected(ZOrder::Back),
+            Command::AlignLeft => self.align_selected(Align::Left),
+            Command::AlignCenter => self.align_selected(Align::Center),
+            Command::AlignRight => self.align_selected(Align::Right),
+            Command::AlignTop => self.align_selected(Align::Top),
+            Command::AlignMiddle => self.align_selected(Align::Middle),
+            Command::AlignBottom => self.align_selected(Align::Bottom),
+            Command::DistributeHorizontally => self.align_selected(Align::DistributeHorizontally),
+            Command::DistributeVertically => self.align_selected(Align::DistributeVertically),
+            Command::Delete => {
+                let selected_id = self.shapes.selected_shapes.to_vec();
+                self.replace_selected(vec![]);
+                let changed = !selected_id.is_empty();
+                self.remove_shapes(selected_id);
+                changed
+            }
+            Command::Deselect => {
+                let changed = !self.shapes.selected_shapes.is_empty();
+                self.replace_selected(vec![]);
+                changed
+            }
+            Command::SelectAll => self.select_all(),
+            Command::Group => self.group_selected(),
+            Command::Ungroup => self.ungroup_selected(),
+            Command::FlipHorizontal => self.flip_selected(true),
+            Command::FlipVertical => self.flip_selected(false),
+            Command::CopyStyle => self.copy_style(),
+            Command::PasteStyle => self.paste_style(),
+        }
+    }
+
+    /// Switches to the tool, which always changes the state.
+    fn switch_tool(&mut self, tool: impl Into<Tool>) -> bool {
+        self.set_tool(tool.into());
+        true
+    }
+
+    pub fn set_fading(&mut self, ids: Vec<Id>) {
+        self.shapes.fading = ids;
+        self.shapes.version.increment();
+    }
+
+    pub fn set_editing(&mut self, id: Option<Id>) {
+        self.shapes.editing = id;
         self.shapes.version.increment();
     }
 
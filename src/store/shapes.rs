@@ -1,12 +1,203 @@
 use serde::{Deserialize, Serialize};
 
+use crate::types::connectors::Connector;
 use crate::types::ids::Id;
-use crate::types::shapes::Shape;
+use crate::types::images::Images;
+use crate::types::presets::Presets;
+use crate::types::shapes::{BBox, Shape, ShapeType};
 use crate::types::version::Version;
 
+#[derive(Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Group {
+    pub id: Id,
+    /// shape or group ids
+    pub members: Vec<Id>,
+}
+
 #[derive(Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Shapes {
     pub shapes: Vec<Shape>,
     pub selected_shapes: Vec<Id>,
+    #[serde(default)]
+    pub groups: Vec<Group>,
     pub version: Version,
+    /// pictures shown by image shapes, stored once per distinct content
+    #[serde(default)]
+    pub images: Images,
+    /// named styles of the document
+    #[serde(default)]
+    pub presets: Presets,
+    /// shapes drawn faded, such as those about to be erased
+    #[serde(skip)]
+    pub fading: Vec<Id>,
+    /// path whose nodes are shown for editing
+    #[serde(skip)]
+    pub editing: Option<Id>,
+}
+
+impl Shapes {
+    pub fn get_group(&self, id: &Id) -> Option<&Group> {
+        self.groups.iter().find(|group| group.id.eq(id))
+    }
+
+    pub fn parent(&self, id: &Id) -> Option<&Id> {
+        self.groups
+            .iter()
+            .find(|group| group.members.contains(id))
+            .map(|group| &group.id)
+    }
+
+    /// Outermost group containing `id` below `within` (or `id` itself if it is not grouped).
+    pub fn outermost(&self, id: &Id, within: Option<&Id>) -> Id {
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            if Some(parent) == within {
+                break;
+            }
+            current = parent;
+        }
+        current.clone()
+    }
+
+    pub fn is_descendant(&self, id: &Id, ancestor: &Id) -> bool {
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            if parent.eq(ancestor) {
+                return true;
+            }
+            current = parent;
+        }
+        false
+    }
+
+    /// Shape ids of `id`, expanding groups recursively.
+    pub fn leaves(&self, id: &Id) -> Vec<Id> {
+        match self.get_group(id) {
+            Some(group) => group
+                .members
+                .iter()
+                .flat_map(|member| self.leaves(member))
+                .collect(),
+            None => vec![id.clone()],
+        }
+    }
+
+    /// Distinct outermost units (groups or shapes) of the given shapes.
+    pub fn units(&self, ids: &[Id], within: Option<&Id>) -> Vec<Id> {
+        let mut units: Vec<Id> = vec![];
+        for id in ids {
+            let unit = self.outermost(id, within);
+            if !units.contains(&unit) {
+                units.push(unit);
+            }
+        }
+        units
+    }
+
+    /// Selected shapes merged into the largest groups which are selected entirely.
+    pub fn selected_units(&self) -> Vec<Id> {
+        let mut units: Vec<Id> = vec![];
+        for id in &self.selected_shapes {
+            let mut unit = id;
+            while let Some(parent) = self.parent(unit) {
+                if !self
+                    .leaves(parent)
+                    .iter()
+                    .all(|leaf| self.selected_shapes.contains(leaf))
+                {
+                    break;
+                }
+                unit = parent;
+            }
+            if !units.contains(unit) {
+                units.push(unit.clone());
+            }
+        }
+        units
+    }
+
+    /// The selected shape when it is the only one.
+    pub fn single_selected(&self) -> Option<&Shape> {
+        let [id] = self.selected_shapes.as_slice() else {
+            return None;
+        };
+        self.shapes.iter().find(|shape| shape.get_id().eq(id))
+    }
+
+    pub fn bbox_of(&self, ids: &[Id]) -> Option<BBox> {
+        let mut bboxes = self
+            .shapes
+            .iter()
+            .filter(|shape| ids.contains(shape.get_id()))
+            .map(|shape| shape.bbox());
+        let mut union = bboxes.next()?;
+        for bbox in bboxes {
+            union.add_bbox(&bbox);
+        }
+        Some(union)
+    }
+
+    /// Boxes of the visible shapes other than `exclude`, used for alignment guides.
+    pub fn other_bboxes(&self, exclude: &[Id]) -> Vec<BBox> {
+        self.shapes
+            .iter()
+            .filter(|shape| !shape.is_hidden() && !exclude.contains(shape.get_id()))
+            .map(|shape| shape.bbox())
+            .collect()
+    }
+
+    /// Routes again the connectors which moved or whose shapes changed since last routed.
+    pub fn update_connectors(&mut self) {
+        for index in 0..self.shapes.len() {
+            let shape = &self.shapes[index];
+            let ShapeType::Connector(connector) = shape.get_shape_type() else {
+                continue;
+            };
+            if !connector.is_outdated(shape, &self.shapes) {
+                continue;
+            }
+            let (start, end) = connector.endpoints(shape, &self.shapes);
+            if let Some((bbox, connector)) =
+                Connector::new(start, end, connector.routing, &self.shapes)
+            {
+                self.shapes[index].set_connector(bbox, connector);
+            }
+        }
+    }
+
+    /// Drops the pictures no image shape shows anymore.
+    pub fn prune_images(&mut self) {
+        let shapes = &self.shapes;
+        self.images.retain(|hash, _| {
+            shapes.iter().any(|shape| {
+                matches!(shape.get_shape_type(), ShapeType::Image { hash: shown } if shown == hash)
+            })
+        });
+    }
+
+    /// Drops members which no longer exist and dissolves groups left with a single member.
+    pub fn prune_groups(&mut self) {
+        loop {
+            let existing: Vec<Id> = self
+                .shapes
+                .iter()
+                .map(|shape| shape.get_id().clone())
+                .chain(self.groups.iter().map(|group| group.id.clone()))
+                .collect();
+            for group in self.groups.iter_mut() {
+                group.members.retain(|member| existing.contains(member));
+            }
+            let Some(index) = self.groups.iter().position(|group| group.members.len() < 2) else {
+                break;
+            };
+            let group = self.groups.remove(index);
+            for parent in self.groups.iter_mut() {
+                if let Some(position) = parent.members.iter().position(|x| x.eq(&group.id)) {
+                    parent
+                        .members
+                        .splice(position..=position, group.members.iter().cloned());
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,49 @@
+use strum_macros::{Display, EnumIter};
+
+#[derive(EnumIter, Display, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+    Top,
+    Middle,
+    Bottom,
+    DistributeHorizontally,
+    DistributeVertically,
+}
+
+impl Align {
+    pub fn button_icon(&self) -> &'static str {
+        match self {
+            Self::Left => "ti-layout-align-left",
+            Self::Center => "ti-layout-align-center",
+            Self::Right => "ti-layout-align-right",
+            Self::Top => "ti-layout-align-top",
+            Self::Middle => "ti-layout-align-middle",
+            Self::Bottom => "ti-layout-align-bottom",
+            Self::DistributeHorizontally => "ti-layout-distribute-vertical",
+            Self::DistributeVertically => "ti-layout-distribute-horizontal",
+        }
+    }
+
+    pub fn button_title(&self) -> &'static str {
+        match self {
+            Self::Left => "Align left",
+            Self::Center => "Align center",
+            Self::Right => "Align right",
+            Self::Top => "Align top",
+            Self::Middle => "Align middle",
+            Self::Bottom => "Align bottom",
+            Self::DistributeHorizontally => "Distribute horizontally",
+            Self::DistributeVertically => "Distribute vertically",
+        }
+    }
+
+    /// Distributing needs at least three items, aligning two.
+    pub fn min_items(&self) -> usize {
+        match self {
+            Self::DistributeHorizontally | Self::DistributeVertically => 3,
+            _ => 2,
+        }
+    }
+}
@@ -11,6 +11,19 @@ pub enum Color {
     Orange,
 }
 
+impl Color {
+    /// Red, green and blue of the named css color.
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            Self::Black => (0, 0, 0),
+            Self::Red => (255, 0, 0),
+            Self::Green => (0, 128, 0),
+            Self::Blue => (0, 0, 255),
+            Self::Orange => (255, 165, 0),
+        }
+    }
+}
+
 #[derive(EnumString, EnumIter, Display, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum BackgroundColor {
     Magenta,
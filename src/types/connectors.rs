@@ -0,0 +1,537 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+use web_sys::CanvasRenderingContext2d;
+
+use crate::types::events::Point;
+use crate::types::ids::Id;
+use crate::types::shapes::{BBox, Draw, Shape, ShapeType};
+use crate::types::svg::{number, path_data};
+use crate::types::version::Version;
+use crate::utils::{distance_to_polyline, nearest_on_polygon};
+
+/// Place on a shape where a connector is attached.
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize, Serialize)]
+pub enum Anchor {
+    Center,
+    Top,
+    Right,
+    Bottom,
+    Left,
+    /// any point on the outline, relative to the box of the shape
+    Edge(Point),
+}
+
+impl Anchor {
+    pub const FIXED: [Self; 5] = [
+        Self::Center,
+        Self::Top,
+        Self::Right,
+        Self::Bottom,
+        Self::Left,
+    ];
+
+    /// Position in the untransformed box, from 0 to 1 on each axis.
+    fn relative(&self) -> Point {
+        match self {
+            Self::Center => (0.5, 0.5),
+            Self::Top => (0.5, 0.0),
+            Self::Right => (1.0, 0.5),
+            Self::Bottom => (0.5, 1.0),
+            Self::Left => (0.0, 0.5),
+            Self::Edge(point) => *point,
+        }
+    }
+
+    /// Outward direction from the nearest side of the untransformed box.
+    fn normal(&self) -> Option<Point> {
+        let (x, y) = self.relative();
+        if matches!(self, Self::Center) {
+            return None;
+        }
+        let sides = [
+            (y, (0.0, -1.0)),
+            (1.0 - x, (1.0, 0.0)),
+            (1.0 - y, (0.0, 1.0)),
+            (x, (-1.0, 0.0)),
+        ];
+        sides
+            .into_iter()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, normal)| normal)
+    }
+
+    /// Position on the canvas, on the outline of the shape unless it is the center.
+    pub fn position(&self, shape: &Shape) -> Point {
+        shape.transform_point(&self.local_position(shape))
+    }
+
+    fn local_position(&self, shape: &Shape) -> Point {
+        let frame = shape.frame();
+        let point = frame.absolute(&self.relative());
+        if matches!(self, Self::Center) {
+            return point;
+        }
+        let outline = shape.get_shape_type().get_drawable(&frame).outline();
+        nearest_on_polygon(&point, &outline).unwrap_or(point)
+    }
+
+    /// Outward direction on the canvas, none for the center.
+    fn direction(&self, shape: &Shape) -> Option<Point> {
+        let (nx, ny) = self.normal()?;
+        let (x, y) = self.local_position(shape);
+        let (x1, y1) = shape.transform_point(&(x, y));
+        let (x2, y2) = shape.transform_point(&(x + nx, y + ny));
+        Some((x2 - x1, y2 - y1))
+    }
+
+    /// Anchor of the shape near the point, or else the point relative to the shape.
+    pub fn at(shape: &Shape, point: &Point, margin: f64) -> Self {
+        Self::FIXED
+            .into_iter()
+            .find(|anchor| {
+                let (x, y) = anchor.position(shape);
+                (x - point.0).hypot(y - point.1) <= margin
+            })
+            .unwrap_or_else(|| Self::Edge(shape.frame().relative(&shape.untransform_point(point))))
+    }
+}
+
+/// End of a connector, either at a point or attached to a shape.
+#[derive(Clone, Deserialize, Serialize)]
+pub enum Endpoint {
+    Free(Point),
+    Bound {
+        id: Id,
+        anchor: Anchor,
+        /// version of the shape the connector was routed against
+        version: Version,
+    },
+}
+
+impl Endpoint {
+    pub fn bound(shape: &Shape, anchor: Anchor) -> Self {
+        Self::Bound {
+            id: shape.get_id().clone(),
+            anchor,
+            version: shape.get_version().clone(),
+        }
+    }
+
+    fn get_shape<'a>(&self, shapes: &'a [Shape]) -> Option<&'a Shape> {
+        match self {
+            Self::Free(_) => None,
+            Self::Bound { id, .. } => shapes.iter().find(|shape| shape.get_id().eq(id)),
+        }
+    }
+
+    /// Position on the canvas along with the direction to leave it in, if attached.
+    fn resolve(&self, shapes: &[Shape]) -> Option<(Point, Option<Point>)> {
+        match self {
+            Self::Free(point) => Some((*point, None)),
+            Self::Bound { anchor, .. } => {
+                let shape = self.get_shape(shapes)?;
+                Some((anchor.position(shape), anchor.direction(shape)))
+            }
+        }
+    }
+
+    /// Same endpoint recording the current version of its shape.
+    fn refreshed(&self, shapes: &[Shape]) -> Self {
+        match (self, self.get_shape(shapes)) {
+            (Self::Bound { anchor, .. }, Some(shape)) => Self::bound(shape, *anchor),
+            _ => self.clone(),
+        }
+    }
+
+    fn is_outdated(&self, shapes: &[Shape]) -> bool {
+        match self {
+            Self::Free(_) => false,
+            Self::Bound { version, .. } => self
+                .get_shape(shapes)
+                .is_none_or(|shape| shape.get_version().ne(version)),
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Display, EnumIter, Deserialize, Serialize)]
+pub enum Routing {
+    #[default]
+    Straight,
+    Elbow,
+    Curved,
+}
+
+impl Routing {
+    /// length of the first and last segment of an elbow leaving a shape
+    const STUB: f64 = 20.0;
+    /// distance kept from obstacles when routing around them
+    const GAP: f64 = 15.0;
+    /// distance from the endpoints within which obstacles are avoided
+    const NEARBY: f64 = 100.0;
+    /// extra length counted for each bend, to prefer simpler routes
+    const BEND: f64 = 10.0;
+
+    pub fn button_icon(&self) -> &'static str {
+        match self {
+            Self::Straight => "ti-line",
+            Self::Elbow => "ti-route-2",
+            Self::Curved => "ti-vector-spline",
+        }
+    }
+
+    /// Points of the route, the end points and two control points when curved.
+    fn route(
+        &self,
+        (start, start_direction): (Point, Option<Point>),
+        (end, end_direction): (Point, Option<Point>),
+        obstacles: &[BBox],
+    ) -> Vec<Point> {
+        let toward = |(x1, y1): Point, (x2, y2): Point| match (x2 - x1).abs() >= (y2 - y1).abs() {
+            true => ((x2 - x1).signum(), 0.0),
+            false => (0.0, (y2 - y1).signum()),
+        };
+        let offset = |(x, y): Point, (dx, dy): Point, length: f64| {
+            let norm = dx.hypot(dy).max(f64::EPSILON);
+            (x + dx / norm * length, y + dy / norm * length)
+        };
+        match self {
+            Self::Straight => vec![start, end],
+            Self::Curved => {
+                let reach = (end.0 - start.0).hypot(end.1 - start.1) / 2.0;
+                let start_direction = start_direction.unwrap_or_else(|| toward(start, end));
+                let end_direction = end_direction.unwrap_or_else(|| toward(end, start));
+                vec![
+                    start,
+                    offset(start, start_direction, reach),
+                    offset(end, end_direction, reach),
+                    end,
+                ]
+            }
+            Self::Elbow => {
+                // leave attached shapes straight out, along the nearest axis
+                let stub = |point: Point, direction: Option<Point>| match direction {
+                    Some(direction) => offset(point, toward((0.0, 0.0), direction), Self::STUB),
+                    None => point,
+                };
+                let from = stub(start, start_direction);
+                let to = stub(end, end_direction);
+                let mut points = vec![start];
+                points.extend(Self::elbow(from, to, obstacles));
+                points.push(end);
+                Self::simplify(points)
+            }
+        }
+    }
+
+    /// Orthogonal path crossing the fewest obstacles, and then the shortest counting bends.
+    fn elbow(from: Point, to: Point, obstacles: &[BBox]) -> Vec<Point> {
+        // only obstacles around the endpoints are worth going around
+        let mut area = BBox::from_corner(&from, &to);
+        area.add_padding(Self::NEARBY);
+        let obstacles: Vec<&BBox> = obstacles.iter().filter(|x| x.intersects(&area)).collect();
+        let mut xs = vec![from.0, to.0, (from.0 + to.0) / 2.0];
+        let mut ys = vec![from.1, to.1, (from.1 + to.1) / 2.0];
+        for bbox in &obstacles {
+            xs.extend([bbox.left - Self::GAP, bbox.right() + Self::GAP]);
+            ys.extend([bbox.top - Self::GAP, bbox.bottom() + Self::GAP]);
+        }
+        let two_bends = xs
+            .iter()
+            .map(|x| vec![from, (*x, from.1), (*x, to.1), to])
+            .chain(ys.iter().map(|y| vec![from, (from.0, *y), (to.0, *y), to]));
+        let four_bends = xs.iter().flat_map(|x| {
+            ys.iter().flat_map(move |y| {
+                [
+                    vec![from, (*x, from.1), (*x, *y), (to.0, *y), to],
+                    vec![from, (from.0, *y), (*x, *y), (*x, to.1), to],
+                ]
+            })
+        });
+        let score = |path: &Vec<Point>| {
+            let crossings = path
+                .windows(2)
+                .map(|segment| {
+                    let bbox = BBox::from_corner(&segment[0], &segment[1]);
+                    obstacles
+                        .iter()
+                        .filter(|obstacle| Self::crosses(&bbox, obstacle))
+                        .count()
+                })
+                .sum::<usize>();
+            let length: f64 = path
+                .windows(2)
+                .map(|segment| (segment[1].0 - segment[0].0).hypot(segment[1].1 - segment[0].1))
+                .sum();
+            (crossings, length + Self::BEND * path.len() as f64)
+        };
+        two_bends
+            .chain(four_bends)
+            .map(|path| (score(&path), path))
+            .min_by(|(a, _), (b, _)| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+            .map_or_else(|| vec![from, to], |(_, path)| path)
+    }
+
+    /// Whether the box of an axis aligned segment passes through the inside of the obstacle.
+    fn crosses(segment: &BBox, obstacle: &BBox) -> bool {
+        segment.right() > obstacle.left + 1.0
+            && segment.left < obstacle.right() - 1.0
+            && segment.bottom() > obstacle.top + 1.0
+            && segment.top < obstacle.bottom() - 1.0
+    }
+
+    /// Drops repeated points and points in the middle of straight runs.
+    fn simplify(points: Vec<Point>) -> Vec<Point> {
+        let mut simplified: Vec<Point> = vec![];
+        for point in points {
+            if simplified.last() == Some(&point) {
+                continue;
+            }
+            if let [.., (x1, y1), (x2, y2)] = simplified[..]
+                && ((x1 == x2 && x2 == point.0) || (y1 == y2 && y2 == point.1))
+            {
+                simplified.pop();
+            }
+            simplified.push(point);
+        }
+        simplified
+    }
+}
+
+/// Line between two endpoints which follows the shapes it is attached to.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Connector {
+    pub start: Endpoint,
+    pub end: Endpoint,
+    pub routing: Routing,
+    /// route relative to the box of the shape, from 0 to 1 on each axis
+    points: Vec<Point>,
+    /// version of the connector shape when it was routed
+    #[serde(default)]
+    routed: Version,
+}
+
+impl Connector {
+    /// Connector routed around the other shapes along with its box, none if an endpoint is
+    /// attached to a missing shape.
+    pub fn new(
+        start: Endpoint,
+        end: Endpoint,
+        routing: Routing,
+        shapes: &[Shape],
+    ) -> Option<(BBox, Self)> {
+        let obstacles: Vec<BBox> = shapes
+            .iter()
+            .filter(|shape| {
+                !shape.is_hidden() && !matches!(shape.get_shape_type(), ShapeType::Connector(_))
+            })
+            .map(|shape| shape.bbox())
+            .collect();
+        let points = routing.route(start.resolve(shapes)?, end.resolve(shapes)?, &obstacles);
+        let bbox = BBox::around(&points);
+        let connector = Self {
+            start: start.refreshed(shapes),
+            end: end.refreshed(shapes),
+            routing,
+            points: points.iter().map(|point| bbox.relative(point)).collect(),
+            routed: Version::default(),
+        };
+        Some((bbox, connector))
+    }
+
+    pub fn get_drawable(&self, bbox: &BBox) -> ConnectorPath {
+        ConnectorPath {
+            points: self
+                .points
+                .iter()
+                .map(|point| bbox.absolute(point))
+                .collect(),
+            curved: self.routing == Routing::Curved,
+        }
+    }
+
+    /// Same connector marked as routed for the given version of its shape.
+    pub fn routed_at(self, version: &Version) -> Self {
+        Self {
+            routed: version.clone(),
+            ..self
+        }
+    }
+
+    /// Whether the connector or a shape it is attached to changed since it was routed.
+    pub fn is_outdated(&self, shape: &Shape, shapes: &[Shape]) -> bool {
+        self.routed.ne(shape.get_version())
+            || self.start.is_outdated(shapes)
+            || self.end.is_outdated(shapes)
+    }
+
+    /// Endpoints to route again with, detaching from deleted shapes at the drawn position
+    /// and moving free ends along with the connector.
+    pub fn endpoints(&self, shape: &Shape, shapes: &[Shape]) -> (Endpoint, Endpoint) {
+        let outline = shape.get_drawable().outline();
+        let moved = self.routed.ne(shape.get_version());
+        let update = |endpoint: &Endpoint, drawn: Option<&Point>| match (endpoint, drawn) {
+            (Endpoint::Free(_), Some(point)) if moved => Endpoint::Free(*point),
+            (Endpoint::Bound { .. }, Some(point)) if endpoint.get_shape(shapes).is_none() => {
+                Endpoint::Free(*point)
+            }
+            _ => endpoint.clone(),
+        };
+        (
+            update(&self.start, outline.first()),
+            update(&self.end, outline.last()),
+        )
+    }
+}
+
+/// Route of a connector with an arrow head at its end.
+#[derive(Clone, Default)]
+pub struct ConnectorPath {
+    points: Vec<Point>,
+    /// cubic curve through two control points instead of straight segments
+    curved: bool,
+}
+
+impl ConnectorPath {
+    const ARROW_LENGTH: f64 = 10.0;
+    const ARROW_ANGLE: f64 = std::f64::consts::PI / 7.0;
+    const CURVE_POINTS: usize = 24;
+
+    fn arrow_head(&self) -> Option<[Point; 2]> {
+        let end = self.points.last()?;
+        let (x, y) = self.points.iter().rev().find(|point| point.ne(&end))?;
+        let angle = (y - end.1).atan2(x - end.0);
+        Some([-Self::ARROW_ANGLE, Self::ARROW_ANGLE].map(|offset| {
+            let (sin, cos) = (angle + offset).sin_cos();
+            (
+                end.0 + Self::ARROW_LENGTH * cos,
+                end.1 + Self::ARROW_LENGTH * sin,
+            )
+        }))
+    }
+}
+
+impl Draw for ConnectorPath {
+    fn new(bbox: &BBox) -> Self {
+        Self {
+            points: vec![(bbox.left, bbox.top), (bbox.right(), bbox.bottom())],
+            curved: false,
+        }
+    }
+
+    fn bbox(&self) -> BBox {
+        BBox::around(&self.points)
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        let Some(((x, y), rest)) = self.points.split_first() else {
+            return;
+        };
+        context.move_to(*x, *y);
+        match rest {
+            [(x1, y1), (x2, y2), (x, y)] if self.curved => {
+                context.bezier_curve_to(*x1, *y1, *x2, *y2, *x, *y);
+            }
+            _ => rest.iter().for_each(|(x, y)| context.line_to(*x, *y)),
+        }
+        if let (Some([left, right]), Some(end)) = (self.arrow_head(), self.points.last()) {
+            context.move_to(left.0, left.1);
+            context.line_to(end.0, end.1);
+            context.line_to(right.0, right.1);
+        }
+    }
+
+    fn contains(&self, point: &Point, margin: f64) -> bool {
+        self.distance(point) <= margin
+    }
+
+    fn distance(&self, point: &Point) -> f64 {
+        distance_to_polyline(point, &self.outline())
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        match self.points[..] {
+            [start, (x1, y1), (x2, y2), end] if self.curved => (0..=Self::CURVE_POINTS)
+                .map(|index| {
+                    let t = index as f64 / Self::CURVE_POINTS as f64;
+                    let u = 1.0 - t;
+                    let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+                    (
+                        a * start.0 + b * x1 + c * x2 + d * end.0,
+                        a * start.1 + b * y1 + c * y2 + d * end.1,
+                    )
+                })
+                .collect(),
+            _ => self.points.clone(),
+        }
+    }
+
+    fn strokes(&self) -> Vec<(Vec<Point>, bool)> {
+        let head = match (self.arrow_head(), self.points.last()) {
+            (Some([left, right]), Some(end)) => Some((vec![left, *end, right], false)),
+            _ => None,
+        };
+        std::iter::once((self.outline(), false))
+            .chain(head)
+            .collect()
+    }
+
+    fn svg(&self) -> String {
+        let Some(((x, y), rest)) = self.points.split_first() else {
+            return String::new();
+        };
+        let mut data = format!("M{} {}", number(*x), number(*y));
+        match rest {
+            [(x1, y1), (x2, y2), (x, y)] if self.curved => {
+                let points = [x1, y1, x2, y2, x, y].map(|value| number(*value));
+                data.push_str(&format!("C{}", points.join(" ")));
+            }
+            _ => rest
+                .iter()
+                .for_each(|(x, y)| data.push_str(&format!("L{} {}", number(*x), number(*y)))),
+        }
+        if let (Some([left, right]), Some(end)) = (self.arrow_head(), self.points.last()) {
+            data.push_str(&path_data(&[left, *end, right], false));
+        }
+        format!(r#"<path d="{data}"/>"#)
+    }
+}
+
+/// Anchor points of a shape shown while connecting.
+pub struct Anchors(Vec<Point>);
+
+impl Anchors {
+    const SIZE: f64 = 4.0;
+
+    pub fn of(shape: &Shape) -> Self {
+        Self(
+            Anchor::FIXED
+                .iter()
+                .map(|anchor| anchor.position(shape))
+                .collect(),
+        )
+    }
+}
+
+impl Draw for Anchors {
+    fn new(bbox: &BBox) -> Self {
+        Self(vec![bbox.center()])
+    }
+
+    fn bbox(&self) -> BBox {
+        BBox::around(&self.0)
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        context.save();
+        context.set_fill_style_str("blue");
+        for (x, y) in &self.0 {
+            context.fill_rect(
+                x - Self::SIZE / 2.0,
+                y - Self::SIZE / 2.0,
+                Self::SIZE,
+                Self::SIZE,
+            );
+        }
+        context.restore();
+    }
+}
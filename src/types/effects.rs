@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use web_sys::CanvasRenderingContext2d;
+
+use crate::types::colors::Color;
+use crate::types::shapes::BBox;
+use crate::types::svg::number;
+
+/// Shadow cast by a shape below and beside it.
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Shadow {
+    pub enabled: bool,
+    pub offset_x: i32,
+    pub offset_y: i32,
+    /// blur of the shadow edges, as the canvas `shadowBlur`
+    pub blur: u32,
+    pub color: Color,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            offset_x: 4,
+            offset_y: 4,
+            blur: 8,
+            color: Color::Black,
+        }
+    }
+}
+
+impl Shadow {
+    /// shadows are see through, whatever their color
+    const OPACITY: f64 = 0.4;
+
+    fn rgba(&self) -> String {
+        let (red, green, blue) = self.color.rgb();
+        format!("rgba({red}, {green}, {blue}, {})", Self::OPACITY)
+    }
+}
+
+/// Optional effects drawn along with a shape.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Effects {
+    pub shadow: Shadow,
+    /// blur of the whole shape in pixels, none when zero
+    pub blur: u32,
+}
+
+impl Effects {
+    /// reach of a blur relative to its standard deviation, beyond which it is invisible
+    const SPREAD: f64 = 3.0;
+
+    pub fn is_none(&self) -> bool {
+        !self.shadow.enabled && self.blur == 0
+    }
+
+    /// Standard deviation of the shadow blur, which the canvas takes doubled.
+    fn shadow_deviation(&self) -> f64 {
+        self.shadow.blur as f64 / 2.0
+    }
+
+    /// Sets up the context so that what is drawn next has the effects.
+    pub fn apply(&self, context: &CanvasRenderingContext2d) {
+        if self.blur > 0 {
+            context.set_filter(&format!("blur({}px)", self.blur));
+        }
+        if self.shadow.enabled {
+            context.set_shadow_color(&self.shadow.rgba());
+            context.set_shadow_offset_x(self.shadow.offset_x as f64);
+            context.set_shadow_offset_y(self.shadow.offset_y as f64);
+            context.set_shadow_blur(self.shadow.blur as f64);
+        }
+    }
+
+    /// Box of what is drawn for a shape in the box, with the effects spreading around it.
+    pub fn bounds(&self, bbox: &BBox) -> BBox {
+        let spread = self.blur as f64 * Self::SPREAD;
+        let mut bounds = bbox.clone();
+        bounds.add_padding(spread);
+        if self.shadow.enabled {
+            let mut shadow =
+                bbox.translate(self.shadow.offset_x as f64, self.shadow.offset_y as f64);
+            shadow.add_padding(spread + self.shadow_deviation() * Self::SPREAD);
+            bounds.add_bbox(&shadow);
+        }
+        bounds
+    }
+
+    /// SVG filter with the id giving the effects to the shape in the box, none without effects.
+    pub fn svg_filter(&self, id: &str, bbox: &BBox) -> Option<String> {
+        if self.is_none() {
+            return None;
+        }
+        let mut primitives = String::new();
+        if self.blur > 0 {
+            primitives.push_str(&format!(
+                r#"<feGaussianBlur stdDeviation="{}"/>"#,
+                self.blur
+            ));
+        }
+        if self.shadow.enabled {
+            let (red, green, blue) = self.shadow.color.rgb();
+            primitives.push_str(&format!(
+                r#"<feDropShadow dx="{}" dy="{}" stdDeviation="{}" flood-color="rgb({red}, {green}, {blue})" flood-opacity="{}"/>"#,
+                self.shadow.offset_x,
+                self.shadow.offset_y,
+                number(self.shadow_deviation()),
+                Shadow::OPACITY,
+            ));
+        }
+        let bounds = self.bounds(bbox);
+        Some(format!(
+            r#"<filter id="{id}" filterUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}">{primitives}</filter>"#,
+            number(bounds.left),
+            number(bounds.top),
+            number(bounds.width),
+            number(bounds.height),
+        ))
+    }
+}
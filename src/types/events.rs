@@ -1,17 +1,22 @@
 pub type Point = (f64, f64);
 
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub struct Modifiers {
+    /// control or meta (cmd) key
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
 #[non_exhaustive]
 pub enum CanvasEvent {
-    PointerEventStart(Point),
-    /// currently not used any where
-    #[allow(dead_code)]
+    PointerEventStart(Point, Modifiers),
     Hover(Point),
-    DragMove((Point, Point)),
-    DragEnd((Point, Point)),
-    /// currently not used any where
-    #[allow(dead_code)]
-    Click(Point),
-    KeyPress(String),
+    DragMove((Point, Point), Modifiers),
+    DragEnd((Point, Point), Modifiers),
+    Click(Point, Modifiers),
+    DoubleClick(Point),
+    KeyPress(String, Modifiers),
     DeselectTool,
     SelectTool,
 }
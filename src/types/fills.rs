@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+use web_sys::CanvasRenderingContext2d;
+use web_sys::js_sys::Array;
+
+use crate::types::events::Point;
+use crate::types::gradients::Gradient;
+use crate::types::shapes::BBox;
+use crate::types::svg::number;
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Display, EnumIter, Deserialize, Serialize)]
+pub enum FillPattern {
+    #[default]
+    Solid,
+    Hatch,
+    CrossHatch,
+    Dots,
+    Linear,
+    Radial,
+    None,
+}
+
+impl FillPattern {
+    pub fn button_icon(&self) -> &'static str {
+        match self {
+            Self::Solid => "ti-square-filled",
+            Self::Hatch => "ti-texture",
+            Self::CrossHatch => "ti-grid-pattern",
+            Self::Dots => "ti-grid-dots",
+            Self::Linear => "ti-square-half",
+            Self::Radial => "ti-circle-dot",
+            Self::None => "ti-square-off",
+        }
+    }
+}
+
+/// How the inside of a shape is painted with its background color, or with a gradient.
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FillStyle {
+    pub pattern: FillPattern,
+    /// distance between the lines or dots of the pattern, in pixels
+    pub spacing: u32,
+    /// direction of the lines of the pattern, in degrees
+    pub angle: u32,
+    /// used by the linear and radial patterns
+    pub gradient: Gradient,
+}
+
+impl Default for FillStyle {
+    fn default() -> Self {
+        Self {
+            pattern: FillPattern::default(),
+            spacing: 8,
+            angle: 45,
+            gradient: Gradient::default(),
+        }
+    }
+}
+
+impl FillStyle {
+    pub const MIN_SPACING: u32 = 2;
+    pub const MAX_ANGLE: u32 = 180;
+    const LINE_WIDTH: f64 = 1.0;
+
+    pub fn is_none(&self) -> bool {
+        self.pattern == FillPattern::None
+    }
+
+    pub fn is_gradient(&self) -> bool {
+        matches!(self.pattern, FillPattern::Linear | FillPattern::Radial)
+    }
+
+    pub fn radians(&self) -> f64 {
+        (self.angle % Self::MAX_ANGLE) as f64 * std::f64::consts::PI / 180.0
+    }
+
+    fn spacing(&self) -> f64 {
+        self.spacing.max(Self::MIN_SPACING) as f64
+    }
+
+    fn dot_radius(&self) -> f64 {
+        (self.spacing() / 5.0).min(2.0)
+    }
+
+    /// Range of tile indices along the direction of the angle (`across` unset) or across it
+    /// which the box covers.
+    fn tiles(&self, bbox: &BBox, across: bool) -> std::ops::RangeInclusive<i64> {
+        let (sin, cos) = self.radians().sin_cos();
+        let values = bbox.corners().map(|(x, y)| match across {
+            true => y * cos - x * sin,
+            false => x * cos + y * sin,
+        });
+        let min = values.into_iter().fold(f64::INFINITY, f64::min);
+        let max = values.into_iter().fold(f64::NEG_INFINITY, f64::max);
+        let spacing = self.spacing();
+        (min / spacing).floor() as i64 - 1..=(max / spacing).ceil() as i64
+    }
+
+    /// Point at `along` the direction of the angle and `across` it.
+    fn point(&self, along: f64, across: f64) -> Point {
+        let (sin, cos) = self.radians().sin_cos();
+        (along * cos - across * sin, along * sin + across * cos)
+    }
+
+    /// Lines of the pattern over the box, laid out as the tiles of `svg_pattern`.
+    pub fn lines(&self, bbox: &BBox) -> Vec<(Point, Point)> {
+        let spacing = self.spacing();
+        let (along, across) = (self.tiles(bbox, false), self.tiles(bbox, true));
+        let (first, last) = (*along.start() as f64, *along.end() as f64 + 1.0);
+        let mut lines: Vec<(Point, Point)> = across
+            .clone()
+            .map(|index| {
+                let offset = (index as f64 + 0.5) * spacing;
+                (
+                    self.point(first * spacing, offset),
+                    self.point(last * spacing, offset),
+                )
+            })
+            .collect();
+        if self.pattern == FillPattern::CrossHatch {
+            let (first, last) = (*across.start() as f64, *across.end() as f64 + 1.0);
+            lines.extend(along.map(|index| {
+                let offset = (index as f64 + 0.5) * spacing;
+                (
+                    self.point(offset, first * spacing),
+                    self.point(offset, last * spacing),
+                )
+            }));
+        }
+        lines
+    }
+
+    /// Centers of the dots of the pattern over the box.
+    fn dots(&self, bbox: &BBox) -> Vec<Point> {
+        let spacing = self.spacing();
+        let across = self.tiles(bbox, true);
+        self.tiles(bbox, false)
+            .flat_map(|along| {
+                across.clone().map(move |across| {
+                    (
+                        (along as f64 + 0.5) * spacing,
+                        (across as f64 + 0.5) * spacing,
+                    )
+                })
+            })
+            .map(|(along, across)| self.point(along, across))
+            .collect()
+    }
+
+    /// Fills the path traced on the context, clipping the pattern over the box to it.
+    pub fn fill(&self, context: &CanvasRenderingContext2d, color: &str, bbox: &BBox) {
+        match self.pattern {
+            FillPattern::None => {}
+            FillPattern::Solid => {
+                context.set_fill_style_str(color);
+                context.fill();
+            }
+            FillPattern::Hatch | FillPattern::CrossHatch => {
+                context.save();
+                context.clip();
+                context.begin_path();
+                for ((x1, y1), (x2, y2)) in self.lines(bbox) {
+                    context.move_to(x1, y1);
+                    context.line_to(x2, y2);
+                }
+                context.set_stroke_style_str(color);
+                context.set_line_width(Self::LINE_WIDTH);
+                context.set_line_dash(&Array::new()).unwrap();
+                context.stroke();
+                context.restore();
+            }
+            FillPattern::Dots => {
+                context.save();
+                context.clip();
+                context.begin_path();
+                let radius = self.dot_radius();
+                for (x, y) in self.dots(bbox) {
+                    context.move_to(x + radius, y);
+                    let _ = context.arc(x, y, radius, 0.0, std::f64::consts::TAU);
+                }
+                context.set_fill_style_str(color);
+                context.fill();
+                context.restore();
+            }
+            FillPattern::Linear => self.gradient.fill(context, false, bbox),
+            FillPattern::Radial => self.gradient.fill(context, true, bbox),
+        }
+    }
+
+    /// SVG pattern or gradient with the id painting this fill, for the fills other than solid.
+    pub fn svg_paint(&self, id: &str, color: &str) -> Option<String> {
+        let spacing = number(self.spacing());
+        let half = number(self.spacing() / 2.0);
+        let tile = match self.pattern {
+            FillPattern::None | FillPattern::Solid => return None,
+            FillPattern::Linear => return Some(self.gradient.svg(id, false)),
+            FillPattern::Radial => return Some(self.gradient.svg(id, true)),
+            FillPattern::Hatch => {
+                format!(r#"<path d="M0 {half}H{spacing}" stroke="{color}" fill="none"/>"#)
+            }
+            FillPattern::CrossHatch => format!(
+                r#"<path d="M0 {half}H{spacing}M{half} 0V{spacing}" stroke="{color}" fill="none"/>"#
+            ),
+            FillPattern::Dots => format!(
+                r#"<circle cx="{half}" cy="{half}" r="{}" fill="{color}" stroke="none"/>"#,
+                number(self.dot_radius())
+            ),
+        };
+        Some(format!(
+            r#"<pattern id="{id}" patternUnits="userSpaceOnUse" width="{spacing}" height="{spacing}" patternTransform="rotate({})" stroke-width="{}">{tile}</pattern>"#,
+            self.angle % Self::MAX_ANGLE,
+            number(Self::LINE_WIDTH),
+        ))
+    }
+}
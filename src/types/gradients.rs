@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use web_sys::{CanvasGradient, CanvasRenderingContext2d};
+
+use crate::types::colors::BackgroundColor;
+use crate::types::events::Point;
+use crate::types::shapes::BBox;
+use crate::types::svg::number;
+
+/// Color of a gradient at an offset along it.
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GradientStop {
+    /// in percent of the gradient length
+    pub offset: u32,
+    pub color: BackgroundColor,
+}
+
+/// Gradient laid out in the box of the shape, so that it moves and scales with it.
+///
+/// Positions are in percent of the box, linear gradients run across the whole box along
+/// their angle and radial ones spread from their center.
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Gradient {
+    /// sorted by offset
+    pub stops: Vec<GradientStop>,
+    /// direction of a linear gradient, in degrees clockwise from the right
+    pub angle: u32,
+    /// center of a radial gradient
+    pub center: (i32, i32),
+    /// radius of a radial gradient
+    pub radius: u32,
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self {
+            stops: vec![
+                GradientStop {
+                    offset: 0,
+                    color: BackgroundColor::Cyan,
+                },
+                GradientStop {
+                    offset: 100,
+                    color: BackgroundColor::Magenta,
+                },
+            ],
+            angle: 0,
+            center: (50, 50),
+            radius: 50,
+        }
+    }
+}
+
+impl Gradient {
+    pub const MAX_ANGLE: u32 = 360;
+    pub const MIN_STOPS: usize = 2;
+    pub const MAX_OFFSET: u32 = 100;
+
+    fn radians(&self) -> f64 {
+        (self.angle % Self::MAX_ANGLE) as f64 * std::f64::consts::PI / 180.0
+    }
+
+    /// Start and end of a linear gradient in the unit box, through its center.
+    pub fn line(&self) -> (Point, Point) {
+        let (sin, cos) = self.radians().sin_cos();
+        // long enough for the lines across the ends to touch the corners
+        let half = (cos.abs() + sin.abs()) / 2.0;
+        (
+            (0.5 - cos * half, 0.5 - sin * half),
+            (0.5 + cos * half, 0.5 + sin * half),
+        )
+    }
+
+    /// Center of a radial gradient in the unit box.
+    pub fn unit_center(&self) -> Point {
+        (self.center.0 as f64 / 100.0, self.center.1 as f64 / 100.0)
+    }
+
+    pub fn unit_radius(&self) -> f64 {
+        self.radius as f64 / 100.0
+    }
+
+    /// Points the angle from the center of the unit box towards the point.
+    pub fn aim_at(&mut self, (x, y): &Point) {
+        let degrees = (y - 0.5).atan2(x - 0.5).to_degrees().round() as i32;
+        self.angle = degrees.rem_euclid(Self::MAX_ANGLE as i32) as u32;
+    }
+
+    pub fn move_center(&mut self, (x, y): &Point) {
+        self.center = ((x * 100.0).round() as i32, (y * 100.0).round() as i32);
+    }
+
+    /// Makes the circle of a radial gradient pass through the point of the unit box.
+    pub fn reach(&mut self, (x, y): &Point) {
+        let (center_x, center_y) = self.unit_center();
+        self.radius = ((x - center_x).hypot(y - center_y) * 100.0).round() as u32;
+    }
+
+    /// Keeps the stops sorted after their offsets changed.
+    pub fn sort(&mut self) {
+        self.stops.sort_by_key(|stop| stop.offset);
+    }
+
+    /// Adds a stop halfway into the widest gap between two stops.
+    pub fn split(&mut self) {
+        let widest = self
+            .stops
+            .windows(2)
+            .enumerate()
+            .max_by_key(|(_, pair)| pair[1].offset - pair[0].offset);
+        let Some((index, pair)) = widest else {
+            return;
+        };
+        let stop = GradientStop {
+            offset: (pair[0].offset + pair[1].offset) / 2,
+            color: pair[0].color.clone(),
+        };
+        self.stops.insert(index + 1, stop);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if self.stops.len() > Self::MIN_STOPS && index < self.stops.len() {
+            self.stops.remove(index);
+        }
+    }
+
+    /// Canvas gradient in the unit box, to be used with the context scaled to the box.
+    fn canvas(&self, context: &CanvasRenderingContext2d, radial: bool) -> CanvasGradient {
+        let gradient = match radial {
+            true => {
+                let (x, y) = self.unit_center();
+                context
+                    .create_radial_gradient(x, y, 0.0, x, y, self.unit_radius())
+                    .unwrap()
+            }
+            false => {
+                let ((x1, y1), (x2, y2)) = self.line();
+                context.create_linear_gradient(x1, y1, x2, y2)
+            }
+        };
+        for stop in &self.stops {
+            let offset = stop.offset.min(Self::MAX_OFFSET) as f32 / 100.0;
+            let _ = gradient.add_color_stop(offset, &stop.color.to_string());
+        }
+        gradient
+    }
+
+    /// Fills the path traced on the context with the gradient laid out in the box.
+    pub fn fill(&self, context: &CanvasRenderingContext2d, radial: bool, bbox: &BBox) {
+        context.save();
+        // the fill style follows the transform at the time of filling, not of tracing
+        context.translate(bbox.left, bbox.top).unwrap();
+        context.scale(bbox.width, bbox.height).unwrap();
+        context.set_fill_style_canvas_gradient(&self.canvas(context, radial));
+        context.fill();
+        context.restore();
+    }
+
+    /// SVG gradient with the id, laid out in the box of the element it paints.
+    pub fn svg(&self, id: &str, radial: bool) -> String {
+        let stops: String = self
+            .stops
+            .iter()
+            .map(|stop| {
+                format!(
+                    r#"<stop offset="{}%" stop-color="{}"/>"#,
+                    stop.offset.min(Self::MAX_OFFSET),
+                    stop.color
+                )
+            })
+            .collect();
+        match radial {
+            true => {
+                let (x, y) = self.unit_center();
+                format!(
+                    r#"<radialGradient id="{id}" cx="{}" cy="{}" r="{}">{stops}</radialGradient>"#,
+                    number(x),
+                    number(y),
+                    number(self.unit_radius()),
+                )
+            }
+            false => {
+                let ((x1, y1), (x2, y2)) = self.line();
+                format!(
+                    r#"<linearGradient id="{id}" x1="{}" y1="{}" x2="{}" y2="{}">{stops}</linearGradient>"#,
+                    number(x1),
+                    number(y1),
+                    number(x2),
+                    number(y2),
+                )
+            }
+        }
+    }
+}
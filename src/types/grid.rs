@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+use web_sys::CanvasRenderingContext2d;
+
+use crate::types::events::Point;
+
+#[derive(
+    EnumString, EnumIter, Display, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize,
+)]
+pub enum GridStyle {
+    #[default]
+    Dots,
+    Lines,
+}
+
+impl GridStyle {
+    pub fn button_icon(&self) -> &'static str {
+        match self {
+            Self::Dots => "ti-grid-dots",
+            Self::Lines => "ti-border-all",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Grid {
+    pub spacing: u32,
+    pub style: GridStyle,
+    pub visible: bool,
+    pub snap: bool,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            spacing: 20,
+            style: GridStyle::default(),
+            visible: true,
+            snap: false,
+        }
+    }
+}
+
+impl Grid {
+    pub const MIN_SPACING: u32 = 5;
+
+    fn spacing(&self) -> f64 {
+        self.spacing.max(Self::MIN_SPACING) as f64
+    }
+
+    pub fn snap_value(&self, value: f64) -> f64 {
+        (value / self.spacing()).round() * self.spacing()
+    }
+
+    pub fn snap_point(&self, (x, y): &Point) -> Point {
+        (self.snap_value(*x), self.snap_value(*y))
+    }
+
+    pub fn draw(&self, context: &CanvasRenderingContext2d, width: f64, height: f64) {
+        if !self.visible {
+            return;
+        }
+        let spacing = self.spacing();
+        context.save();
+        context.set_fill_style_str("lightgray");
+        match self.style {
+            GridStyle::Dots => {
+                let mut x = 0.0;
+                while x <= width {
+                    let mut y = 0.0;
+                    while y <= height {
+                        context.fill_rect(x - 1.0, y - 1.0, 2.0, 2.0);
+                        y += spacing;
+                    }
+                    x += spacing;
+                }
+            }
+            GridStyle::Lines => {
+                let mut x = 0.0;
+                while x <= width {
+                    context.fill_rect(x - 0.5, 0.0, 1.0, height);
+                    x += spacing;
+                }
+                let mut y = 0.0;
+                while y <= height {
+                    context.fill_rect(0.0, y - 0.5, width, 1.0);
+                    y += spacing;
+                }
+            }
+        }
+        context.restore();
+    }
+}
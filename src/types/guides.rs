@@ -0,0 +1,200 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::types::events::Point;
+use crate::types::shapes::{BBox, Draw};
+
+/// Line segment from `start` to `end` along x at height `at`, or transposed.
+type Gap = (f64, f64, f64);
+
+/// Alignment lines and equal spacing hints against other shapes.
+#[derive(Clone, Default)]
+pub struct Guides {
+    lines: Vec<(Point, Point)>,
+    labels: Vec<(Point, String)>,
+}
+
+fn transpose(bbox: &BBox) -> BBox {
+    BBox {
+        left: bbox.top,
+        top: bbox.left,
+        width: bbox.height,
+        height: bbox.width,
+    }
+}
+
+fn stops(bbox: &BBox) -> [f64; 3] {
+    [bbox.left, bbox.left + bbox.width / 2.0, bbox.right()]
+}
+
+fn same_row<'a>(bbox: &'a BBox, others: &'a [BBox]) -> impl Iterator<Item = &'a BBox> + 'a {
+    others.iter().filter(move |other| {
+        !std::ptr::eq(*other, bbox) && other.top <= bbox.bottom() && bbox.top <= other.bottom()
+    })
+}
+
+fn left_of<'a>(bbox: &'a BBox, others: &'a [BBox]) -> Option<&'a BBox> {
+    same_row(bbox, others)
+        .filter(|other| other.right() <= bbox.left + Guides::THRESHOLD)
+        .max_by(|a, b| a.right().total_cmp(&b.right()))
+}
+
+fn right_of<'a>(bbox: &'a BBox, others: &'a [BBox]) -> Option<&'a BBox> {
+    same_row(bbox, others)
+        .filter(|other| other.left >= bbox.right() - Guides::THRESHOLD)
+        .min_by(|a, b| a.left.total_cmp(&b.left))
+}
+
+fn gap(first: &BBox, second: &BBox) -> Gap {
+    let top = first.top.max(second.top);
+    let bottom = first.bottom().min(second.bottom());
+    (first.right(), second.left, (top + bottom) / 2.0)
+}
+
+/// Horizontal offsets which make the gaps on either side of the box equal.
+fn spacings(bbox: &BBox, others: &[BBox]) -> Vec<(f64, [Gap; 2])> {
+    let mut spacings = vec![];
+    let left = left_of(bbox, others);
+    let right = right_of(bbox, others);
+    if let (Some(left), Some(right)) = (left, right) {
+        let offset = (left.right() + right.left - bbox.width) / 2.0 - bbox.left;
+        let moved = bbox.translate(offset, 0.0);
+        spacings.push((offset, [gap(left, &moved), gap(&moved, right)]));
+    }
+    if let Some(left) = left
+        && let Some(before) = left_of(left, others)
+    {
+        let offset = left.right() + (left.left - before.right()) - bbox.left;
+        let moved = bbox.translate(offset, 0.0);
+        spacings.push((offset, [gap(before, left), gap(left, &moved)]));
+    }
+    if let Some(right) = right
+        && let Some(after) = right_of(right, others)
+    {
+        let offset = right.left - (after.left - right.right()) - bbox.right();
+        let moved = bbox.translate(offset, 0.0);
+        spacings.push((offset, [gap(&moved, right), gap(right, after)]));
+    }
+    spacings.retain(|(_, gaps)| gaps.iter().all(|(start, end, _)| start < end));
+    spacings
+}
+
+/// Smallest horizontal offset within the threshold aligning or spacing the box.
+fn closest_offset(bbox: &BBox, others: &[BBox], spacing: bool) -> Option<f64> {
+    let alignments = stops(bbox)
+        .into_iter()
+        .flat_map(|mine| others.iter().flat_map(stops).map(move |other| other - mine))
+        .collect::<Vec<_>>();
+    let spacings = if spacing {
+        spacings(bbox, others)
+    } else {
+        vec![]
+    };
+    alignments
+        .into_iter()
+        .chain(spacings.into_iter().map(|(offset, _)| offset))
+        .filter(|offset| offset.abs() <= Guides::THRESHOLD)
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+}
+
+impl Guides {
+    pub const THRESHOLD: f64 = 5.0;
+    const EPSILON: f64 = 0.5;
+
+    /// Moves the box so its edges or center line up with other boxes.
+    pub fn snap_bbox(bbox: &BBox, others: &[BBox]) -> (BBox, Self) {
+        let transposed: Vec<BBox> = others.iter().map(transpose).collect();
+        let dx = closest_offset(bbox, others, true).unwrap_or_default();
+        let dy = closest_offset(&transpose(bbox), &transposed, true).unwrap_or_default();
+        let snapped = bbox.translate(dx, dy);
+        let guides = Self::find(&snapped, others, true);
+        (snapped, guides)
+    }
+
+    /// Moves the point onto edges or centers of other boxes.
+    pub fn snap_point(point: &Point, others: &[BBox]) -> (Point, Self) {
+        let bbox = BBox::from_corner(point, point);
+        let transposed: Vec<BBox> = others.iter().map(transpose).collect();
+        let dx = closest_offset(&bbox, others, false).unwrap_or_default();
+        let dy = closest_offset(&transpose(&bbox), &transposed, false).unwrap_or_default();
+        let snapped = (point.0 + dx, point.1 + dy);
+        let guides = Self::find(&BBox::from_corner(&snapped, &snapped), others, false);
+        (snapped, guides)
+    }
+
+    fn find(bbox: &BBox, others: &[BBox], spacing: bool) -> Self {
+        let mut guides = Self::default();
+        guides.find_along(bbox, others, spacing, false);
+        let transposed: Vec<BBox> = others.iter().map(transpose).collect();
+        guides.find_along(&transpose(bbox), &transposed, spacing, true);
+        guides
+    }
+
+    fn find_along(&mut self, bbox: &BBox, others: &[BBox], spacing: bool, transposed: bool) {
+        let point = |x: f64, y: f64| if transposed { (y, x) } else { (x, y) };
+        for other in others {
+            for x in stops(other) {
+                if stops(bbox)
+                    .iter()
+                    .any(|mine| (mine - x).abs() < Self::EPSILON)
+                {
+                    let top = bbox.top.min(other.top);
+                    let bottom = bbox.bottom().max(other.bottom());
+                    self.lines.push((point(x, top), point(x, bottom)));
+                }
+            }
+        }
+        if !spacing {
+            return;
+        }
+        for (offset, gaps) in spacings(bbox, others) {
+            if offset.abs() >= Self::EPSILON {
+                continue;
+            }
+            for (start, end, at) in gaps {
+                self.lines.push((point(start, at), point(end, at)));
+                self.labels.push((
+                    point((start + end) / 2.0, at),
+                    format!("{:.0}", end - start),
+                ));
+            }
+        }
+    }
+}
+
+impl Draw for Guides {
+    fn new(_bbox: &BBox) -> Self {
+        Self::default()
+    }
+
+    fn bbox(&self) -> BBox {
+        let mut points = self.lines.iter().flat_map(|(start, end)| [start, end]);
+        let Some(first) = points.next() else {
+            return BBox::default();
+        };
+        let mut bbox = BBox::from_corner(first, first);
+        for point in points {
+            bbox.add_bbox(&BBox::from_corner(point, point));
+        }
+        bbox
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        context.save();
+        context.set_fill_style_str("magenta");
+        for ((x1, y1), (x2, y2)) in &self.lines {
+            let bbox = BBox::from_corner(&(*x1, *y1), &(*x2, *y2));
+            context.fill_rect(
+                bbox.left - 0.5,
+                bbox.top - 0.5,
+                bbox.width + 1.0,
+                bbox.height + 1.0,
+            );
+        }
+        context.set_font("10px sans-serif");
+        context.set_text_align("center");
+        for ((x, y), label) in &self.labels {
+            context.fill_text(label, *x, *y - 3.0).unwrap();
+        }
+        context.restore();
+    }
+}
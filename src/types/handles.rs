@@ -0,0 +1,339 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::types::events::Point;
+use crate::types::fills::FillPattern;
+use crate::types::paths::{Node, NodePart};
+use crate::types::shapes::{BBox, Draw, Shape, ShapeType};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Handle {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Rotate,
+}
+
+impl Handle {
+    pub const SIZE: f64 = 8.0;
+    /// distance of the rotate handle above the selection
+    const ROTATE_OFFSET: f64 = 25.0;
+
+    pub fn all() -> [Self; 5] {
+        [
+            Self::TopLeft,
+            Self::TopRight,
+            Self::BottomLeft,
+            Self::BottomRight,
+            Self::Rotate,
+        ]
+    }
+
+    pub fn position(&self, bbox: &BBox) -> Point {
+        match self {
+            Self::Rotate => (bbox.center().0, bbox.top - Self::ROTATE_OFFSET),
+            Self::TopLeft => (bbox.left, bbox.top),
+            Self::TopRight => (bbox.right(), bbox.top),
+            Self::BottomLeft => (bbox.left, bbox.bottom()),
+            Self::BottomRight => (bbox.right(), bbox.bottom()),
+        }
+    }
+
+    /// Point which stays in place while resizing or rotating with this handle.
+    pub fn anchor(&self, bbox: &BBox) -> Point {
+        match self {
+            Self::Rotate => return bbox.center(),
+            Self::TopLeft => Self::BottomRight,
+            Self::TopRight => Self::BottomLeft,
+            Self::BottomLeft => Self::TopRight,
+            Self::BottomRight => Self::TopLeft,
+        }
+        .position(bbox)
+    }
+
+    pub fn pointer(&self) -> &'static str {
+        match self {
+            Self::TopLeft | Self::BottomRight => "nwse-resize",
+            Self::TopRight | Self::BottomLeft => "nesw-resize",
+            Self::Rotate => "grab",
+        }
+    }
+
+    pub fn find(bbox: &BBox, point: &Point) -> Option<Self> {
+        Self::all().into_iter().find(|handle| {
+            let (x, y) = handle.position(bbox);
+            (x - point.0).abs() <= Self::SIZE && (y - point.1).abs() <= Self::SIZE
+        })
+    }
+}
+
+/// Resize handles drawn on the corners of the selection.
+#[derive(Clone, Default)]
+pub struct Handles(BBox);
+
+impl Draw for Handles {
+    fn new(bbox: &BBox) -> Self {
+        Self(bbox.clone())
+    }
+
+    fn bbox(&self) -> BBox {
+        self.0.clone()
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        context.save();
+        context.set_stroke_style_str("blue");
+        context.set_fill_style_str("white");
+        let half = Handle::SIZE / 2.0;
+        let (x, y) = Handle::Rotate.position(&self.0);
+        context.begin_path();
+        context.move_to(x, self.0.top);
+        context.line_to(x, y + half);
+        context.stroke();
+        context.begin_path();
+        context.arc(x, y, half, 0.0, std::f64::consts::TAU).unwrap();
+        context.fill();
+        context.stroke();
+        for handle in Handle::all().into_iter().filter(|x| x.ne(&Handle::Rotate)) {
+            let (x, y) = handle.position(&self.0);
+            context.fill_rect(x - half, y - half, Handle::SIZE, Handle::SIZE);
+            context.stroke_rect(x - half, y - half, Handle::SIZE, Handle::SIZE);
+        }
+        context.restore();
+    }
+}
+
+/// Handle dragged to change the corner radius of a single selected rectangle.
+#[derive(Clone, Default)]
+pub struct RadiusHandle(Point);
+
+impl RadiusHandle {
+    /// smallest distance of the handle from the corner, to keep it off the resize handle
+    const MIN_INSET: f64 = 12.0;
+
+    pub fn of(shape: &Shape) -> Option<Self> {
+        if !matches!(shape.get_shape_type(), ShapeType::Rectangle) || !shape.is_interactive() {
+            return None;
+        }
+        let frame = shape.frame();
+        let inset = shape
+            .get_corner_radius()
+            .resolve(&frame)
+            .max(Self::MIN_INSET)
+            .min(frame.width.min(frame.height) / 2.0);
+        Some(Self(
+            shape.transform_point(&(frame.left + inset, frame.top + inset)),
+        ))
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        (self.0.0 - point.0).hypot(self.0.1 - point.1) <= Handle::SIZE
+    }
+}
+
+impl Draw for RadiusHandle {
+    fn new(bbox: &BBox) -> Self {
+        Self((bbox.left, bbox.top))
+    }
+
+    fn bbox(&self) -> BBox {
+        BBox::from_corner(&self.0, &self.0)
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        let (x, y) = self.0;
+        context.save();
+        context.set_stroke_style_str("blue");
+        context.set_fill_style_str("white");
+        context.begin_path();
+        context
+            .arc(x, y, Handle::SIZE / 2.0, 0.0, std::f64::consts::TAU)
+            .unwrap();
+        context.fill();
+        context.stroke();
+        context.restore();
+    }
+}
+
+/// Anchors and control points of a path, shown while drawing or editing it.
+#[derive(Clone, Default)]
+pub struct PathHandles(Vec<Node>);
+
+impl From<Vec<Node>> for PathHandles {
+    fn from(nodes: Vec<Node>) -> Self {
+        Self(nodes)
+    }
+}
+
+impl PathHandles {
+    /// Nodes of a path shape placed on the canvas.
+    pub fn of(shape: &Shape) -> Option<Self> {
+        let path = shape.get_path()?;
+        let nodes = path.nodes.iter();
+        Some(Self(
+            nodes
+                .map(|node| node.map(|point| shape.transform_point(&point)))
+                .collect(),
+        ))
+    }
+
+    /// Node and part under the point, anchors come first and only smooth nodes have handles.
+    pub fn find(&self, point: &Point) -> Option<(usize, NodePart)> {
+        let near = |(x, y): Point| (x - point.0).hypot(y - point.1) <= Handle::SIZE;
+        let anchor = self
+            .0
+            .iter()
+            .position(|node| near(node.anchor))
+            .map(|index| (index, NodePart::Anchor));
+        anchor.or_else(|| {
+            self.0.iter().enumerate().find_map(|(index, node)| {
+                [NodePart::Before, NodePart::After]
+                    .into_iter()
+                    .find(|part| !node.is_corner() && near(node.get(*part)))
+                    .map(|part| (index, part))
+            })
+        })
+    }
+}
+
+impl Draw for PathHandles {
+    fn new(bbox: &BBox) -> Self {
+        Self(vec![Node::corner((bbox.left, bbox.top))])
+    }
+
+    fn bbox(&self) -> BBox {
+        let points: Vec<Point> = self
+            .0
+            .iter()
+            .flat_map(|node| [node.anchor, node.before, node.after])
+            .collect();
+        BBox::around(&points)
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        let half = Handle::SIZE / 2.0;
+        context.save();
+        context
+            .set_line_dash(&web_sys::js_sys::Array::new())
+            .unwrap();
+        context.set_line_width(1.0);
+        context.set_stroke_style_str("blue");
+        context.set_fill_style_str("white");
+        for node in self.0.iter().filter(|node| !node.is_corner()) {
+            let (x, y) = node.anchor;
+            context.begin_path();
+            context.move_to(node.before.0, node.before.1);
+            context.line_to(x, y);
+            context.line_to(node.after.0, node.after.1);
+            context.stroke();
+            for (x, y) in [node.before, node.after] {
+                context.begin_path();
+                context
+                    .arc(x, y, half / 2.0 + 1.0, 0.0, std::f64::consts::TAU)
+                    .unwrap();
+                context.fill();
+                context.stroke();
+            }
+        }
+        for (x, y) in self.0.iter().map(|node| node.anchor) {
+            context.fill_rect(x - half, y - half, Handle::SIZE, Handle::SIZE);
+            context.stroke_rect(x - half, y - half, Handle::SIZE, Handle::SIZE);
+        }
+        context.restore();
+    }
+}
+
+/// Part of a gradient moved by a handle.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GradientPart {
+    /// ends of a linear gradient, turning it around the center of the shape
+    Start,
+    End,
+    Center,
+    /// point on the circle of a radial gradient
+    Radius,
+}
+
+/// Handles dragged to change the gradient of a single selected shape.
+#[derive(Clone, Default)]
+pub struct GradientHandles(Vec<(GradientPart, Point)>);
+
+impl GradientHandles {
+    pub fn of(shape: &Shape) -> Option<Self> {
+        let fill = shape.get_fill();
+        if !shape.is_filled() || !fill.is_gradient() || !shape.is_interactive() {
+            return None;
+        }
+        let frame = shape.frame();
+        let place = |(x, y): Point| {
+            shape.transform_point(&(frame.left + x * frame.width, frame.top + y * frame.height))
+        };
+        let gradient = &fill.gradient;
+        let handles = match fill.pattern {
+            FillPattern::Radial => {
+                let (x, y) = gradient.unit_center();
+                vec![
+                    (GradientPart::Center, place((x, y))),
+                    (GradientPart::Radius, place((x + gradient.unit_radius(), y))),
+                ]
+            }
+            _ => {
+                let (start, end) = gradient.line();
+                vec![
+                    (GradientPart::Start, place(start)),
+                    (GradientPart::End, place(end)),
+                ]
+            }
+        };
+        Some(Self(handles))
+    }
+
+    pub fn find(&self, point: &Point) -> Option<GradientPart> {
+        self.0
+            .iter()
+            .rev()
+            .find(|(_, (x, y))| (x - point.0).hypot(y - point.1) <= Handle::SIZE)
+            .map(|(part, _)| *part)
+    }
+}
+
+impl Draw for GradientHandles {
+    fn new(bbox: &BBox) -> Self {
+        Self(vec![(GradientPart::Center, bbox.center())])
+    }
+
+    fn bbox(&self) -> BBox {
+        let points: Vec<Point> = self.0.iter().map(|(_, point)| *point).collect();
+        BBox::around(&points)
+    }
+
+    /// Line between the handles, with the end or radius handle filled.
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        let half = Handle::SIZE / 2.0;
+        context.save();
+        context
+            .set_line_dash(&web_sys::js_sys::Array::new())
+            .unwrap();
+        context.set_line_width(1.0);
+        context.set_stroke_style_str("blue");
+        context.begin_path();
+        for (index, (_, (x, y))) in self.0.iter().enumerate() {
+            match index {
+                0 => context.move_to(*x, *y),
+                _ => context.line_to(*x, *y),
+            }
+        }
+        context.stroke();
+        for (part, (x, y)) in &self.0 {
+            let filled = matches!(part, GradientPart::End | GradientPart::Radius);
+            context.set_fill_style_str(if filled { "blue" } else { "white" });
+            context.begin_path();
+            context
+                .arc(*x, *y, half, 0.0, std::f64::consts::TAU)
+                .unwrap();
+            context.fill();
+            context.stroke();
+        }
+        context.restore();
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::fnv_hash;
+
 #[derive(Hash, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct Id(String);
 
@@ -8,3 +10,10 @@ impl Default for Id {
         Self(uuid::Uuid::new_v4().to_string())
     }
 }
+
+impl Id {
+    /// Number derived from the id, for randomness which stays the same between redraws.
+    pub fn seed(&self) -> u64 {
+        fnv_hash(&self.0)
+    }
+}
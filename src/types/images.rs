@@ -0,0 +1,200 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use hashbrown::HashMap;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::{CanvasRenderingContext2d, File, FileList, FileReader, HtmlImageElement};
+use yewdux::prelude::Dispatch;
+
+use crate::store::AppState;
+use crate::types::events::Point;
+use crate::types::shapes::{BBox, Draw};
+use crate::types::svg::number;
+use crate::utils::content_hash;
+
+/// Image data urls of the document by content hash.
+pub type Images = BTreeMap<String, Rc<str>>;
+
+/// Types of the files which can be inserted.
+pub const ACCEPTED: [&str; 3] = ["image/png", "image/jpeg", "image/svg+xml"];
+
+/// part of the viewport an inserted image may fill
+const FIT: f64 = 0.8;
+/// size of images which do not tell theirs, such as svg without width and height
+const FALLBACK_SIZE: Point = (300.0, 150.0);
+/// offset between images inserted together
+const CASCADE: f64 = 20.0;
+
+thread_local! {
+    /// decoded images by content hash, shared by every shape showing them
+    static ELEMENTS: RefCell<HashMap<String, HtmlImageElement>> = RefCell::default();
+}
+
+/// Decodes the images of the document not decoded yet, redrawing once each is ready.
+pub fn decode(images: &Images, dispatch: &Dispatch<AppState>) {
+    ELEMENTS.with_borrow_mut(|elements| {
+        for (hash, data) in images {
+            if elements.contains_key(hash) {
+                continue;
+            }
+            let Ok(element) = HtmlImageElement::new() else {
+                continue;
+            };
+            let dispatch = dispatch.clone();
+            let onload = Closure::once_into_js(move || dispatch.reduce_mut(|app| app.set_redraw()));
+            element.set_onload(Some(onload.unchecked_ref()));
+            element.set_src(data);
+            elements.insert(hash.clone(), element);
+        }
+    });
+}
+
+/// Box of the canvas, which inserted images are fitted in.
+pub fn viewport() -> BBox {
+    let canvas = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.query_selector("canvas").ok().flatten());
+    let (width, height) = canvas.map_or((0.0, 0.0), |canvas| {
+        (canvas.client_width() as f64, canvas.client_height() as f64)
+    });
+    BBox {
+        left: 0.0,
+        top: 0.0,
+        width,
+        height,
+    }
+}
+
+/// Inserts the image files centered at the point, scaled down to fit the viewport.
+pub fn insert_files(files: &FileList, center: Point, dispatch: &Dispatch<AppState>) {
+    let viewport = viewport();
+    let files = (0..files.length()).filter_map(|index| files.get(index));
+    let accepted = files.filter(|file| ACCEPTED.contains(&file.type_().as_str()));
+    for (index, file) in accepted.enumerate() {
+        let offset = CASCADE * index as f64;
+        let center = (center.0 + offset, center.1 + offset);
+        read(file, center, viewport.clone(), dispatch.clone());
+    }
+}
+
+fn read(file: File, center: Point, viewport: BBox, dispatch: Dispatch<AppState>) {
+    let Ok(reader) = FileReader::new() else {
+        return;
+    };
+    let onload = {
+        let reader = reader.clone();
+        Closure::once_into_js(move || {
+            if let Some(data) = reader.result().ok().and_then(|result| result.as_string()) {
+                load(data, center, viewport, dispatch);
+            }
+        })
+    };
+    reader.set_onload(Some(onload.unchecked_ref()));
+    let _ = reader.read_as_data_url(&file);
+}
+
+/// Decodes the image to learn its size before adding it.
+fn load(data: String, center: Point, viewport: BBox, dispatch: Dispatch<AppState>) {
+    let Ok(element) = HtmlImageElement::new() else {
+        return;
+    };
+    let onload = {
+        let element = element.clone();
+        let data: Rc<str> = data.as_str().into();
+        Closure::once_into_js(move || {
+            let size = (
+                element.natural_width() as f64,
+                element.natural_height() as f64,
+            );
+            let hash = content_hash(&data);
+            ELEMENTS.with_borrow_mut(|elements| {
+                elements.entry(hash.clone()).or_insert(element);
+            });
+            let bbox = fit(size, center, &viewport);
+            dispatch.reduce_mut(|app| app.add_image(hash, data, &bbox));
+        })
+    };
+    element.set_onload(Some(onload.unchecked_ref()));
+    element.set_src(&data);
+}
+
+/// Box of the size centered at the point, scaled down to fit the viewport.
+fn fit((width, height): Point, (x, y): Point, viewport: &BBox) -> BBox {
+    let (width, height) = match width > 0.0 && height > 0.0 {
+        true => (width, height),
+        false => FALLBACK_SIZE,
+    };
+    let scale = (viewport.width * FIT / width)
+        .min(viewport.height * FIT / height)
+        .min(1.0);
+    let (width, height) = (width * scale, height * scale);
+    BBox {
+        left: x - width / 2.0,
+        top: y - height / 2.0,
+        width,
+        height,
+    }
+}
+
+/// Image stretched over its box.
+#[derive(Clone, Default)]
+pub struct Picture {
+    bbox: BBox,
+    element: Option<HtmlImageElement>,
+}
+
+impl Picture {
+    pub fn of(hash: &str, bbox: &BBox) -> Self {
+        Self {
+            bbox: bbox.clone(),
+            element: ELEMENTS.with_borrow(|elements| elements.get(hash).cloned()),
+        }
+    }
+}
+
+impl Draw for Picture {
+    fn new(bbox: &BBox) -> Self {
+        Self {
+            bbox: bbox.clone(),
+            element: None,
+        }
+    }
+
+    fn bbox(&self) -> BBox {
+        self.bbox.clone()
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        let BBox {
+            left,
+            top,
+            width,
+            height,
+        } = self.bbox;
+        match &self.element {
+            Some(element) if element.complete() => {
+                let _ = context.draw_image_with_html_image_element_and_dw_and_dh(
+                    element, left, top, width, height,
+                );
+            }
+            // outline in place of images still loading
+            _ => context.rect(left, top, width, height),
+        }
+    }
+
+    fn svg(&self) -> String {
+        let Some(element) = &self.element else {
+            return String::new();
+        };
+        format!(
+            r#"<image href="{}" x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none"/>"#,
+            element.src(),
+            number(self.bbox.left),
+            number(self.bbox.top),
+            number(self.bbox.width),
+            number(self.bbox.height),
+        )
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::types::events::Modifiers;
+
+#[derive(EnumString, EnumIter, Display, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Command {
+    SelectTool,
+    RectangleTool,
+    EllipseTool,
+    TriangleTool,
+    DiamondTool,
+    PolygonTool,
+    StarTool,
+    ConnectorTool,
+    PenTool,
+    PencilTool,
+    EraseTool,
+    EyedropperTool,
+    Delete,
+    Deselect,
+    SelectAll,
+    NudgeLeft,
+    NudgeRight,
+    NudgeUp,
+    NudgeDown,
+    NudgeLeftFar,
+    NudgeRightFar,
+    NudgeUpFar,
+    NudgeDownFar,
+    Group,
+    Ungroup,
+    BringToFront,
+    BringForward,
+    SendBackward,
+    SendToBack,
+    AlignLeft,
+    AlignCenter,
+    AlignRight,
+    AlignTop,
+    AlignMiddle,
+    AlignBottom,
+    DistributeHorizontally,
+    DistributeVertically,
+    FlipHorizontal,
+    FlipVertical,
+    CopyStyle,
+    PasteStyle,
+}
+
+impl Command {
+    /// distance the arrow keys move the selection, and with shift held
+    pub const NUDGE: f64 = 1.0;
+    pub const NUDGE_FAR: f64 = 10.0;
+
+    fn default_chord(&self) -> &'static str {
+        match self {
+            Self::SelectTool => "V",
+            Self::RectangleTool => "R",
+            Self::EllipseTool => "O",
+            Self::TriangleTool => "T",
+            Self::DiamondTool => "D",
+            Self::PolygonTool => "N",
+            Self::StarTool => "S",
+            Self::ConnectorTool => "C",
+            Self::PenTool => "B",
+            Self::PencilTool => "P",
+            Self::EraseTool => "E",
+            Self::EyedropperTool => "I",
+            Self::Delete => "Delete",
+            Self::Deselect => "Escape",
+            Self::SelectAll => "Ctrl+A",
+            Self::NudgeLeft => "ArrowLeft",
+            Self::NudgeRight => "ArrowRight",
+            Self::NudgeUp => "ArrowUp",
+            Self::NudgeDown => "ArrowDown",
+            Self::NudgeLeftFar => "Shift+ArrowLeft",
+            Self::NudgeRightFar => "Shift+ArrowRight",
+            Self::NudgeUpFar => "Shift+ArrowUp",
+            Self::NudgeDownFar => "Shift+ArrowDown",
+            Self::Group => "Ctrl+G",
+            Self::Ungroup => "Ctrl+Shift+G",
+            Self::BringToFront => "Ctrl+Shift+]",
+            Self::BringForward => "Ctrl+]",
+            Self::SendBackward => "Ctrl+[",
+            Self::SendToBack => "Ctrl+Shift+[",
+            Self::AlignLeft => "Alt+A",
+            Self::AlignCenter => "Alt+H",
+            Self::AlignRight => "Alt+D",
+            Self::AlignTop => "Alt+W",
+            Self::AlignMiddle => "Alt+V",
+            Self::AlignBottom => "Alt+S",
+            Self::DistributeHorizontally => "Alt+Shift+H",
+            Self::DistributeVertically => "Alt+Shift+V",
+            Self::FlipHorizontal => "Shift+H",
+            Self::FlipVertical => "Shift+V",
+            Self::CopyStyle => "Ctrl+Alt+C",
+            Self::PasteStyle => "Ctrl+Alt+V",
+        }
+    }
+}
+
+/// Key along with the modifiers held, written like `Ctrl+Shift+G`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    key: String,
+    modifiers: Modifiers,
+}
+
+impl KeyChord {
+    pub fn new(key: &str, modifiers: Modifiers) -> Self {
+        // keys typed with shift held are named after the unshifted key
+        let key = match key {
+            "{" => "[",
+            "}" => "]",
+            key => key,
+        };
+        let key = if key.chars().count() == 1 {
+            key.to_lowercase()
+        } else {
+            key.to_string()
+        };
+        Self { key, modifiers }
+    }
+
+    /// Modifier keys pressed on their own do not make a chord.
+    pub fn is_modifier(&self) -> bool {
+        matches!(
+            self.key.as_str(),
+            "Control" | "Shift" | "Alt" | "Meta" | "AltGraph"
+        )
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.key.chars().count() == 1 {
+            write!(f, "{}", self.key.to_uppercase())
+        } else {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = ();
+
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        let (prefix, key) = match chord.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"),
+            None if chord == "+" => ("", "+"),
+            None => chord.rsplit_once('+').unwrap_or(("", chord)),
+        };
+        if key.is_empty() {
+            return Err(());
+        }
+        let mut modifiers = Modifiers::default();
+        for part in prefix.split('+').filter(|part| !part.is_empty()) {
+            match part {
+                "Ctrl" => modifiers.ctrl = true,
+                "Alt" => modifiers.alt = true,
+                "Shift" => modifiers.shift = true,
+                _ => return Err(()),
+            }
+        }
+        Ok(Self::new(key, modifiers))
+    }
+}
+
+impl Serialize for KeyChord {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyChord {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Self::from_str(&value).map_err(|_| serde::de::Error::custom("invalid key chord"))
+    }
+}
+
+/// Key bindings of commands, defaults with the user's overrides on top.
+#[derive(Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Keymap {
+    /// `None` unbinds the command
+    overrides: Vec<(Command, Option<KeyChord>)>,
+}
+
+impl Keymap {
+    pub fn binding(&self, command: &Command) -> Option<KeyChord> {
+        match self
+            .overrides
+            .iter()
+            .find(|(overridden, _)| overridden.eq(command))
+        {
+            Some((_, chord)) => chord.clone(),
+            None => KeyChord::from_str(command.default_chord()).ok(),
+        }
+    }
+
+    pub fn is_overridden(&self, command: &Command) -> bool {
+        self.overrides
+            .iter()
+            .any(|(overridden, _)| overridden.eq(command))
+    }
+
+    pub fn command(&self, chord: &KeyChord) -> Option<Command> {
+        Command::iter().find(|command| self.binding(command).as_ref() == Some(chord))
+    }
+
+    /// Binds the chord to the command, unbinding it from any other command.
+    pub fn set_binding(&mut self, command: Command, chord: KeyChord) {
+        if let Some(other) = self.command(&chord)
+            && other.ne(&command)
+        {
+            self.reset_binding(&other);
+            if self.binding(&other).as_ref() == Some(&chord) {
+                self.overrides.push((other, None));
+            }
+        }
+        self.reset_binding(&command);
+        if self.binding(&command).as_ref() != Some(&chord) {
+            self.overrides.push((command, Some(chord)));
+        }
+    }
+
+    pub fn reset_binding(&mut self, command: &Command) {
+        self.overrides
+            .retain(|(overridden, _)| overridden.ne(command));
+    }
+}
@@ -1,6 +1,23 @@
+pub mod align;
 pub mod colors;
+pub mod connectors;
+pub mod effects;
 pub mod events;
+pub mod fills;
+pub mod gradients;
+pub mod grid;
+pub mod guides;
+pub mod handles;
 pub mod ids;
+pub mod images;
+pub mod keymap;
+pub mod order;
+pub mod paths;
+pub mod polygons;
+pub mod presets;
 pub mod shapes;
+pub mod sketch;
+pub mod strokes;
+pub mod svg;
 pub mod tools;
 pub mod version;
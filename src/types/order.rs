@@ -0,0 +1,29 @@
+use strum_macros::{Display, EnumIter};
+
+#[derive(EnumIter, Display, Clone, Copy, PartialEq, Eq)]
+pub enum ZOrder {
+    Front,
+    Forward,
+    Backward,
+    Back,
+}
+
+impl ZOrder {
+    pub fn button_icon(&self) -> &'static str {
+        match self {
+            Self::Front => "ti-stack-front",
+            Self::Forward => "ti-arrow-up",
+            Self::Backward => "ti-arrow-down",
+            Self::Back => "ti-stack-back",
+        }
+    }
+
+    pub fn button_title(&self) -> &'static str {
+        match self {
+            Self::Front => "Bring to front",
+            Self::Forward => "Bring forward",
+            Self::Backward => "Send backward",
+            Self::Back => "Send to back",
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use web_sys::CanvasRenderingContext2d;
+
+use crate::types::events::Point;
+use crate::types::shapes::{BBox, Draw};
+use crate::types::svg::number;
+use crate::utils::{distance_to_polygon, distance_to_polyline, point_in_polygon};
+
+/// Anchor of a path along with the control points of the curves around it.
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Node {
+    pub anchor: Point,
+    /// control point of the curve arriving at the anchor
+    pub before: Point,
+    /// control point of the curve leaving the anchor
+    pub after: Point,
+}
+
+impl Node {
+    /// Node without handles, the path turns sharply at it.
+    pub fn corner(anchor: Point) -> Self {
+        Self {
+            anchor,
+            before: anchor,
+            after: anchor,
+        }
+    }
+
+    /// Node with the handle after the anchor at `after` and the one before mirrored.
+    pub fn smooth(anchor: Point, after: Point) -> Self {
+        Self {
+            anchor,
+            before: (2.0 * anchor.0 - after.0, 2.0 * anchor.1 - after.1),
+            after,
+        }
+    }
+
+    pub fn is_corner(&self) -> bool {
+        self.before == self.anchor && self.after == self.anchor
+    }
+
+    pub fn get(&self, part: NodePart) -> Point {
+        match part {
+            NodePart::Anchor => self.anchor,
+            NodePart::Before => self.before,
+            NodePart::After => self.after,
+        }
+    }
+
+    /// Moves a part of the node by the offset, the handles follow the anchor and the opposite
+    /// handle is mirrored when `mirror` is set.
+    pub fn moved(&self, part: NodePart, (dx, dy): Point, mirror: bool) -> Self {
+        let offset = |(x, y): Point| (x + dx, y + dy);
+        let (x, y) = self.anchor;
+        match part {
+            NodePart::Anchor => self.map(offset),
+            NodePart::Before if mirror => {
+                let (before_x, before_y) = offset(self.before);
+                Self::smooth(self.anchor, (2.0 * x - before_x, 2.0 * y - before_y))
+            }
+            NodePart::After if mirror => Self::smooth(self.anchor, offset(self.after)),
+            NodePart::Before => Self {
+                before: offset(self.before),
+                ..*self
+            },
+            NodePart::After => Self {
+                after: offset(self.after),
+                ..*self
+            },
+        }
+    }
+
+    pub fn map(&self, map: impl Fn(Point) -> Point) -> Self {
+        Self {
+            anchor: map(self.anchor),
+            before: map(self.before),
+            after: map(self.after),
+        }
+    }
+}
+
+/// Point of a node which can be dragged.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NodePart {
+    Anchor,
+    Before,
+    After,
+}
+
+/// Cubic Bézier curves joining the anchors of the nodes.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct BezierPath {
+    pub nodes: Vec<Node>,
+    /// joins the last node back to the first
+    pub closed: bool,
+}
+
+impl BezierPath {
+    /// points sampled along each curve for hit tests
+    const SAMPLES: usize = 16;
+
+    pub fn new(nodes: Vec<Node>, closed: bool) -> Self {
+        Self { nodes, closed }
+    }
+
+    /// Start, control points and end of each curve.
+    fn segments(&self) -> Vec<[Point; 4]> {
+        let joined = self.nodes.windows(2).map(|pair| (pair[0], pair[1])).chain(
+            match (self.closed, self.nodes.first(), self.nodes.last()) {
+                (true, Some(first), Some(last)) if self.nodes.len() > 1 => Some((*last, *first)),
+                _ => None,
+            },
+        );
+        joined
+            .map(|(from, to)| [from.anchor, from.after, to.before, to.anchor])
+            .collect()
+    }
+
+    /// Box around the curves themselves, control points may lie outside of it.
+    pub fn extent(&self) -> BBox {
+        let mut points: Vec<Point> = self.nodes.iter().map(|node| node.anchor).collect();
+        for segment in self.segments() {
+            let xs = segment.map(|point| point.0);
+            let ys = segment.map(|point| point.1);
+            for t in extrema(&xs).into_iter().chain(extrema(&ys)) {
+                points.push(evaluate(&segment, t));
+            }
+        }
+        BBox::around(&points)
+    }
+
+    /// Path with its points at fractions of the box, from 0 to 1 on each axis.
+    pub fn relative(&self, bbox: &BBox) -> Self {
+        self.map(|point| bbox.relative(&point))
+    }
+
+    /// Path with its points placed in the box, the inverse of `relative`.
+    pub fn absolute(&self, bbox: &BBox) -> Self {
+        self.map(|point| bbox.absolute(&point))
+    }
+
+    pub fn map(&self, map: impl Fn(Point) -> Point) -> Self {
+        Self {
+            nodes: self.nodes.iter().map(|node| node.map(&map)).collect(),
+            closed: self.closed,
+        }
+    }
+}
+
+/// Curve parameters in (0, 1) where the cubic with the coordinates turns back.
+fn extrema([p0, p1, p2, p3]: &[f64; 4]) -> Vec<f64> {
+    // derivative divided by 3 is a t^2 + b t + c
+    let (d0, d1, d2) = (p1 - p0, p2 - p1, p3 - p2);
+    let (a, b, c) = (d0 - 2.0 * d1 + d2, 2.0 * (d1 - d0), d0);
+    let roots = if a.abs() < f64::EPSILON {
+        match b.abs() < f64::EPSILON {
+            true => vec![],
+            false => vec![-c / b],
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        match discriminant < 0.0 {
+            true => vec![],
+            false => {
+                let root = discriminant.sqrt();
+                vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+            }
+        }
+    };
+    roots.into_iter().filter(|t| *t > 0.0 && *t < 1.0).collect()
+}
+
+/// Point of the cubic curve at the parameter.
+fn evaluate([p0, p1, p2, p3]: &[Point; 4], t: f64) -> Point {
+    let u = 1.0 - t;
+    let (w0, w1, w2, w3) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+    (
+        w0 * p0.0 + w1 * p1.0 + w2 * p2.0 + w3 * p3.0,
+        w0 * p0.1 + w1 * p1.1 + w2 * p2.1 + w3 * p3.1,
+    )
+}
+
+impl Draw for BezierPath {
+    fn new(bbox: &BBox) -> Self {
+        Self::new(
+            vec![
+                Node::corner((bbox.left, bbox.top)),
+                Node::corner((bbox.right(), bbox.bottom())),
+            ],
+            false,
+        )
+    }
+
+    fn bbox(&self) -> BBox {
+        self.extent()
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        let Some(first) = self.nodes.first() else {
+            return;
+        };
+        context.move_to(first.anchor.0, first.anchor.1);
+        for [_, (x1, y1), (x2, y2), (x, y)] in self.segments() {
+            context.bezier_curve_to(x1, y1, x2, y2, x, y);
+        }
+        if self.closed {
+            context.close_path();
+        }
+    }
+
+    fn contains(&self, point: &Point, margin: f64) -> bool {
+        (self.closed && point_in_polygon(point, &self.outline())) || self.distance(point) <= margin
+    }
+
+    fn distance(&self, point: &Point) -> f64 {
+        match self.closed {
+            true => distance_to_polygon(point, &self.outline()),
+            false => distance_to_polyline(point, &self.outline()),
+        }
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        let mut outline: Vec<Point> = self.nodes.first().map(|x| x.anchor).into_iter().collect();
+        for segment in self.segments() {
+            outline.extend(
+                (1..=Self::SAMPLES)
+                    .map(|index| evaluate(&segment, index as f64 / Self::SAMPLES as f64)),
+            );
+        }
+        if self.closed && outline.len() > 1 {
+            outline.pop();
+        }
+        outline
+    }
+
+    fn strokes(&self) -> Vec<(Vec<Point>, bool)> {
+        vec![(self.outline(), self.closed)]
+    }
+
+    fn svg(&self) -> String {
+        let Some(first) = self.nodes.first() else {
+            return String::new();
+        };
+        let mut data = format!("M{} {}", number(first.anchor.0), number(first.anchor.1));
+        for [_, (x1, y1), (x2, y2), (x, y)] in self.segments() {
+            let points = [x1, y1, x2, y2, x, y].map(number).join(" ");
+            data.push_str(&format!("C{points}"));
+        }
+        if self.closed {
+            data.push('Z');
+        }
+        format!(r#"<path d="{data}"/>"#)
+    }
+}
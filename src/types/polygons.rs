@@ -0,0 +1,229 @@
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use web_sys::CanvasRenderingContext2d;
+
+use crate::store::AppState;
+use crate::types::events::Point;
+use crate::types::shapes::{BBox, Draw, ShapeType};
+use crate::types::tools::shape_tool::{PolygonOptions, ShapeToolDetails};
+use crate::utils::{distance_to_polygon, point_in_polygon};
+
+/// Closed outline through the vertices, fitted in a box.
+#[derive(Default, Clone)]
+pub struct Vertices {
+    bbox: BBox,
+    points: Vec<Point>,
+}
+
+impl Vertices {
+    /// Vertices placed at fractions of the box, from 0 to 1 on each axis.
+    fn relative(bbox: &BBox, points: &[Point]) -> Self {
+        Self {
+            bbox: bbox.clone(),
+            points: points.iter().map(|point| bbox.absolute(point)).collect(),
+        }
+    }
+
+    /// Vertices at `(angle, radius)` on the ellipse in the box, angles from the top and radii
+    /// relative to the ellipse.
+    fn around(bbox: &BBox, vertices: impl Iterator<Item = (f64, f64)>) -> Self {
+        let (center_x, center_y) = bbox.center();
+        let (radius_x, radius_y) = (bbox.width / 2.0, bbox.height / 2.0);
+        Self {
+            bbox: bbox.clone(),
+            points: vertices
+                .map(|(angle, radius)| {
+                    let (sin, cos) = (angle - FRAC_PI_2).sin_cos();
+                    (
+                        center_x + radius_x * radius * cos,
+                        center_y + radius_y * radius * sin,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Draw for Vertices {
+    fn new(bbox: &BBox) -> Self {
+        Self {
+            bbox: bbox.clone(),
+            points: bbox.corners().to_vec(),
+        }
+    }
+
+    fn bbox(&self) -> BBox {
+        self.bbox.clone()
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        let Some(((x, y), rest)) = self.points.split_first() else {
+            return;
+        };
+        context.move_to(*x, *y);
+        for (x, y) in rest {
+            context.line_to(*x, *y);
+        }
+        context.close_path();
+    }
+
+    fn contains(&self, point: &Point, margin: f64) -> bool {
+        point_in_polygon(point, &self.points) || self.distance(point) <= margin
+    }
+
+    fn distance(&self, point: &Point) -> f64 {
+        distance_to_polygon(point, &self.points)
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+}
+
+/// Implements `Draw` for a wrapper of `Vertices`, built with `$build` from the box.
+macro_rules! polygon_shape {
+    ($shape:ident, $build:expr) => {
+        impl Draw for $shape {
+            fn new(bbox: &BBox) -> Self {
+                let build: fn(&BBox) -> Vertices = $build;
+                Self(build(bbox))
+            }
+
+            fn bbox(&self) -> BBox {
+                self.0.bbox()
+            }
+
+            fn draw(&self, context: &CanvasRenderingContext2d) {
+                self.0.draw(context)
+            }
+
+            fn contains(&self, point: &Point, margin: f64) -> bool {
+                self.0.contains(point, margin)
+            }
+
+            fn distance(&self, point: &Point) -> f64 {
+                self.0.distance(point)
+            }
+
+            fn outline(&self) -> Vec<Point> {
+                self.0.outline()
+            }
+        }
+    };
+}
+
+#[derive(Default, Clone)]
+pub struct Triangle(Vertices);
+
+polygon_shape!(Triangle, |bbox| {
+    Vertices::relative(bbox, &[(0.5, 0.0), (1.0, 1.0), (0.0, 1.0)])
+});
+
+impl ShapeToolDetails for Triangle {
+    fn shape_type(_app_state: &AppState) -> ShapeType {
+        ShapeType::Triangle
+    }
+
+    fn button_icon(&self) -> &'static str {
+        "ti-triangle"
+    }
+
+    fn button_title(&self) -> &'static str {
+        "Triangle drawing tool."
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct Diamond(Vertices);
+
+polygon_shape!(Diamond, |bbox| {
+    Vertices::relative(bbox, &[(0.5, 0.0), (1.0, 0.5), (0.5, 1.0), (0.0, 0.5)])
+});
+
+impl ShapeToolDetails for Diamond {
+    fn shape_type(_app_state: &AppState) -> ShapeType {
+        ShapeType::Diamond
+    }
+
+    fn button_icon(&self) -> &'static str {
+        "ti-square-rotated"
+    }
+
+    fn button_title(&self) -> &'static str {
+        "Diamond drawing tool."
+    }
+}
+
+/// Polygon with equal sides inscribed in the ellipse of the box.
+#[derive(Default, Clone)]
+pub struct RegularPolygon(Vertices);
+
+polygon_shape!(RegularPolygon, |bbox| {
+    RegularPolygon::with_sides(bbox, PolygonOptions::default().sides).0
+});
+
+impl RegularPolygon {
+    pub fn with_sides(bbox: &BBox, sides: u32) -> Self {
+        let angle = TAU / sides as f64;
+        Self(Vertices::around(
+            bbox,
+            (0..sides).map(|index| (angle * index as f64, 1.0)),
+        ))
+    }
+}
+
+impl ShapeToolDetails for RegularPolygon {
+    fn shape_type(app_state: &AppState) -> ShapeType {
+        ShapeType::Polygon {
+            sides: app_state.get_polygon_options().sides,
+        }
+    }
+
+    fn button_icon(&self) -> &'static str {
+        "ti-hexagon"
+    }
+
+    fn button_title(&self) -> &'static str {
+        "Polygon drawing tool."
+    }
+}
+
+/// Star with its inner vertices at `inner_ratio` of the outer ones.
+#[derive(Default, Clone)]
+pub struct Star(Vertices);
+
+polygon_shape!(Star, |bbox| {
+    let options = PolygonOptions::default();
+    Star::with_points(bbox, options.points, options.inner_ratio()).0
+});
+
+impl Star {
+    pub fn with_points(bbox: &BBox, points: u32, inner_ratio: f64) -> Self {
+        let angle = TAU / (2 * points) as f64;
+        Self(Vertices::around(
+            bbox,
+            (0..2 * points).map(|index| {
+                let radius = if index % 2 == 0 { 1.0 } else { inner_ratio };
+                (angle * index as f64, radius)
+            }),
+        ))
+    }
+}
+
+impl ShapeToolDetails for Star {
+    fn shape_type(app_state: &AppState) -> ShapeType {
+        let options = app_state.get_polygon_options();
+        ShapeType::Star {
+            points: options.points,
+            inner_ratio: options.inner_ratio(),
+        }
+    }
+
+    fn button_icon(&self) -> &'static str {
+        "ti-star"
+    }
+
+    fn button_title(&self) -> &'static str {
+        "Star drawing tool."
+    }
+}
@@ -0,0 +1,29 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::colors::{BackgroundColor, Color};
+use crate::types::effects::Effects;
+use crate::types::fills::FillStyle;
+use crate::types::sketch::RenderStyle;
+use crate::types::strokes::{Dash, StrokeWidth};
+
+/// Look of a shape, everything a preset sets.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ShapeStyle {
+    pub color: Color,
+    pub bg_color: Option<BackgroundColor>,
+    #[serde(default)]
+    pub fill: FillStyle,
+    #[serde(default)]
+    pub render_style: RenderStyle,
+    #[serde(default)]
+    pub stroke_width: StrokeWidth,
+    #[serde(default)]
+    pub dash: Dash,
+    #[serde(default)]
+    pub effects: Effects,
+}
+
+/// Named styles by name.
+pub type Presets = BTreeMap<String, ShapeStyle>;
@@ -1,16 +1,37 @@
 use std::cell::RefCell;
+use std::f64::consts::{FRAC_PI_2, PI};
 
 use hashbrown::HashMap;
-use wasm_bindgen::JsValue;
-use web_sys::CanvasRenderingContext2d;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 
 use serde::{Deserialize, Serialize};
+use strum_macros::Display;
 
+use crate::store::AppState;
 use crate::types::colors::{BackgroundColor, Color};
+use crate::types::connectors::{Connector, Routing};
+use crate::types::effects::Effects;
 use crate::types::events::Point;
+use crate::types::fills::FillStyle;
 use crate::types::ids::Id;
+use crate::types::images::Picture;
+use crate::types::paths::BezierPath;
+use crate::types::polygons::{Diamond, RegularPolygon, Star, Triangle};
+use crate::types::presets::ShapeStyle;
+use crate::types::sketch::{RenderStyle, Sketch};
+use crate::types::strokes::{Dash, StrokeWidth};
+use crate::types::svg::{number, path};
+use crate::types::tools::ToolAction;
+use crate::types::tools::connector_tool::Connect;
+use crate::types::tools::pen_tool::Pen;
+use crate::types::tools::pencil_tool::Pencil;
 use crate::types::tools::shape_tool::ShapeToolDetails;
 use crate::types::version::Version;
+use crate::utils::{
+    distance_to_polygon, distance_to_polyline, edges, point_in_polygon, rotate_point,
+    segments_intersect,
+};
 
 #[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
 pub struct BBox {
@@ -30,14 +51,92 @@ impl BBox {
         }
     }
 
-    fn right(&self) -> f64 {
+    /// Largest box from the anchor towards the corner with the proportions of `like`.
+    pub fn from_corner_like(anchor: &Point, (x, y): &Point, like: &BBox) -> Self {
+        if like.width <= 0.0 || like.height <= 0.0 {
+            return Self::from_corner(anchor, &(*x, *y));
+        }
+        let (dx, dy) = (x - anchor.0, y - anchor.1);
+        let scale = (dx.abs() / like.width).max(dy.abs() / like.height);
+        let corner = (
+            anchor.0 + like.width * scale * dx.signum(),
+            anchor.1 + like.height * scale * dy.signum(),
+        );
+        Self::from_corner(anchor, &corner)
+    }
+
+    /// Smallest box around all the points.
+    pub fn around(points: &[Point]) -> Self {
+        let mut points = points.iter();
+        let first = points.next().cloned().unwrap_or_default();
+        let mut bbox = Self::from_corner(&first, &first);
+        for point in points {
+            bbox.add_bbox(&Self::from_corner(point, point));
+        }
+        bbox
+    }
+
+    /// Position of the point in the box, from 0 to 1 on each axis.
+    pub fn relative(&self, (x, y): &Point) -> Point {
+        let relative = |value: f64, start: f64, size: f64| match size > 0.0 {
+            true => (value - start) / size,
+            false => 0.0,
+        };
+        (
+            relative(*x, self.left, self.width),
+            relative(*y, self.top, self.height),
+        )
+    }
+
+    /// Point at a relative position in the box, the inverse of `relative`.
+    pub fn absolute(&self, (x, y): &Point) -> Point {
+        (self.left + x * self.width, self.top + y * self.height)
+    }
+
+    pub fn center(&self) -> Point {
+        (self.left + self.width / 2.0, self.top + self.height / 2.0)
+    }
+
+    pub fn corners(&self) -> [Point; 4] {
+        [
+            (self.left, self.top),
+            (self.right(), self.top),
+            (self.right(), self.bottom()),
+            (self.left, self.bottom()),
+        ]
+    }
+
+    pub fn right(&self) -> f64 {
         self.left + self.width
     }
 
-    fn bottom(&self) -> f64 {
+    pub fn bottom(&self) -> f64 {
         self.top + self.height
     }
 
+    #[must_use]
+    pub fn translate(&self, dx: f64, dy: f64) -> Self {
+        Self {
+            left: self.left + dx,
+            top: self.top + dy,
+            ..self.clone()
+        }
+    }
+
+    /// Scales and moves the box the way `from` would have to change to become `to`.
+    #[must_use]
+    pub fn map(&self, from: &BBox, to: &BBox) -> Self {
+        let scale = |from: f64, to: f64| if from == 0.0 { 1.0 } else { to / from };
+        let scale_x = scale(from.width, to.width);
+        let scale_y = scale(from.height, to.height);
+        Self {
+            left: to.left + (self.left - from.left) * scale_x,
+            top: to.top + (self.top - from.top) * scale_y,
+            width: self.width * scale_x,
+            height: self.height * scale_y,
+        }
+    }
+
     #[must_use]
     pub fn in_(&self, bbox: &BBox) -> bool {
         self.left >= bbox.left
@@ -46,6 +145,14 @@ impl BBox {
             && self.bottom() <= bbox.bottom()
     }
 
+    #[must_use]
+    pub fn intersects(&self, bbox: &BBox) -> bool {
+        self.left <= bbox.right()
+            && bbox.left <= self.right()
+            && self.top <= bbox.bottom()
+            && bbox.top <= self.bottom()
+    }
+
     pub fn contains(&self, point: &Point, margin: f64) -> bool {
         let (x, y) = *point;
         self.top <= y + margin
@@ -85,9 +192,73 @@ where
     fn isin(&self, bbox: &BBox) -> bool {
         self.bbox().in_(bbox)
     }
+    fn intersects(&self, bbox: &BBox) -> bool {
+        self.bbox().intersects(bbox)
+    }
     fn contains(&self, point: &Point, margin: f64) -> bool {
         self.bbox().contains(point, margin)
     }
+    /// Distance from the point to the outline of the shape.
+    fn distance(&self, point: &Point) -> f64 {
+        distance_to_polygon(point, &self.outline())
+    }
+    /// Polygon approximating the outline of the shape.
+    fn outline(&self) -> Vec<Point> {
+        self.bbox().corners().to_vec()
+    }
+    /// Lines tracing the shape and whether each is closed, for the sketchy style.
+    fn strokes(&self) -> Vec<(Vec<Point>, bool)> {
+        vec![(self.outline(), true)]
+    }
+    /// Fills the shape traced by `draw` with the color in the style.
+    fn fill(&self, context: &CanvasRenderingContext2d, color: &str, style: &FillStyle) {
+        style.fill(context, color, &self.bbox());
+    }
+    /// SVG elements tracing the shape, painted by the enclosing group.
+    fn svg(&self) -> String {
+        path(&self.outline(), true)
+    }
+    /// SVG elements filling the shape with `paint`, the color or the pattern of the style.
+    fn svg_fill(&self, paint: &str, _color: &str, _style: &FillStyle) -> String {
+        format!(r#"<g fill="{paint}" stroke="none">{}</g>"#, self.svg())
+    }
+}
+
+/// Radius of the rounded corners of a rectangle.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum CornerRadius {
+    /// in pixels
+    Absolute(f64),
+    /// fraction of the shorter side
+    Proportional(f64),
+}
+
+impl Default for CornerRadius {
+    fn default() -> Self {
+        Self::Absolute(0.0)
+    }
+}
+
+impl CornerRadius {
+    /// Radius in pixels for the box, at most half of its shorter side.
+    pub fn resolve(&self, bbox: &BBox) -> f64 {
+        let shorter = bbox.width.min(bbox.height);
+        let radius = match self {
+            Self::Absolute(radius) => *radius,
+            Self::Proportional(ratio) => ratio * shorter,
+        };
+        radius.clamp(0.0, shorter / 2.0)
+    }
+
+    /// Same kind of radius resolving to `radius` pixels for the box.
+    pub fn with_pixels(&self, radius: f64, bbox: &BBox) -> Self {
+        let shorter = bbox.width.min(bbox.height);
+        match self {
+            Self::Absolute(_) => Self::Absolute(radius),
+            Self::Proportional(_) if shorter > 0.0 => Self::Proportional(radius / shorter),
+            Self::Proportional(ratio) => Self::Proportional(*ratio),
+        }
+    }
 }
 
 #[derive(Default, Clone)]
@@ -96,6 +267,28 @@ pub struct Rectangle {
     top: f64,
     width: f64,
     height: f64,
+    /// corner radius in pixels
+    radius: f64,
+}
+
+impl Rectangle {
+    /// points on the outline of each rounded corner
+    const CORNER_POINTS: usize = 8;
+
+    pub fn rounded(bbox: &BBox, radius: f64) -> Self {
+        Self {
+            radius,
+            ..Self::new(bbox)
+        }
+    }
+
+    /// Signed distance to the outline, negative inside.
+    fn signed_distance(&self, (x, y): &Point) -> f64 {
+        let (center_x, center_y) = self.bbox().center();
+        let qx = (x - center_x).abs() - self.width / 2.0 + self.radius;
+        let qy = (y - center_y).abs() - self.height / 2.0 + self.radius;
+        qx.max(0.0).hypot(qy.max(0.0)) + qx.max(qy).min(0.0) - self.radius
+    }
 }
 
 impl Draw for Rectangle {
@@ -105,6 +298,7 @@ impl Draw for Rectangle {
             top: bbox.top,
             width: bbox.width,
             height: bbox.height,
+            radius: 0.0,
         }
     }
 
@@ -118,12 +312,77 @@ impl Draw for Rectangle {
     }
 
     fn draw(&self, context: &CanvasRenderingContext2d) {
-        context.rect(self.left, self.top, self.width, self.height);
+        if self.radius <= 0.0 {
+            context.rect(self.left, self.top, self.width, self.height);
+            return;
+        }
+        let (right, bottom) = (self.left + self.width, self.top + self.height);
+        context.move_to(self.left + self.radius, self.top);
+        context
+            .arc_to(right, self.top, right, bottom, self.radius)
+            .unwrap();
+        context
+            .arc_to(right, bottom, self.left, bottom, self.radius)
+            .unwrap();
+        context
+            .arc_to(self.left, bottom, self.left, self.top, self.radius)
+            .unwrap();
+        context
+            .arc_to(self.left, self.top, right, self.top, self.radius)
+            .unwrap();
+        context.close_path();
+    }
+
+    fn contains(&self, point: &Point, margin: f64) -> bool {
+        self.signed_distance(point) <= margin
+    }
+
+    fn svg(&self) -> String {
+        let radius = match self.radius > 0.0 {
+            true => format!(r#" rx="{0}" ry="{0}""#, number(self.radius)),
+            false => String::new(),
+        };
+        format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}"{radius}/>"#,
+            number(self.left),
+            number(self.top),
+            number(self.width),
+            number(self.height),
+        )
+    }
+
+    fn distance(&self, point: &Point) -> f64 {
+        self.signed_distance(point).abs()
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        if self.radius <= 0.0 {
+            return self.bbox().corners().to_vec();
+        }
+        let inner = BBox {
+            left: self.left + self.radius,
+            top: self.top + self.radius,
+            width: self.width - 2.0 * self.radius,
+            height: self.height - 2.0 * self.radius,
+        };
+        // corners clockwise from the top left, each with the angle its arc starts at
+        let corners = inner.corners();
+        let starts = [PI, -FRAC_PI_2, 0.0, FRAC_PI_2];
+        corners
+            .iter()
+            .zip(starts)
+            .flat_map(|((x, y), start)| {
+                (0..=Self::CORNER_POINTS).map(move |index| {
+                    let angle = start + FRAC_PI_2 * index as f64 / Self::CORNER_POINTS as f64;
+                    (x + self.radius * angle.cos(), y + self.radius * angle.sin())
+                })
+            })
+            .collect()
     }
 }
 
 impl ShapeToolDetails for Rectangle {
-    fn shape_type() -> ShapeType {
+    fn shape_type(_app_state: &AppState) -> ShapeType {
         ShapeType::Rectangle
     }
 
@@ -159,6 +418,301 @@ impl Draw for Selection {
     }
 }
 
+/// Freeform selection outline.
+#[derive(Clone, Default)]
+pub struct Lasso(Vec<Point>);
+
+impl From<Vec<Point>> for Lasso {
+    fn from(points: Vec<Point>) -> Self {
+        Self(points)
+    }
+}
+
+impl Draw for Lasso {
+    fn new(bbox: &BBox) -> Self {
+        Self(bbox.corners().to_vec())
+    }
+
+    fn bbox(&self) -> BBox {
+        BBox::around(&self.0)
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        let Some(((x, y), rest)) = self.0.split_first() else {
+            return;
+        };
+        context.save();
+        context.set_stroke_style_str("blue");
+        let dashes = web_sys::js_sys::Array::new();
+        dashes.push(&JsValue::from_f64(5.0));
+        context.set_line_dash(&dashes).unwrap();
+        context.begin_path();
+        context.move_to(*x, *y);
+        for (x, y) in rest {
+            context.line_to(*x, *y);
+        }
+        context.close_path();
+        context.stroke();
+        context.restore();
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        self.0.clone()
+    }
+}
+
+/// Open path through the points, drawn for freehand strokes.
+#[derive(Clone, Default)]
+pub struct Polyline(Vec<Point>);
+
+impl From<Vec<Point>> for Polyline {
+    fn from(points: Vec<Point>) -> Self {
+        Self(points)
+    }
+}
+
+impl Draw for Polyline {
+    fn new(bbox: &BBox) -> Self {
+        Self(vec![(bbox.left, bbox.top), (bbox.right(), bbox.bottom())])
+    }
+
+    fn bbox(&self) -> BBox {
+        BBox::around(&self.0)
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        let Some(((x, y), rest)) = self.0.split_first() else {
+            return;
+        };
+        context.move_to(*x, *y);
+        for (x, y) in rest {
+            context.line_to(*x, *y);
+        }
+    }
+
+    fn contains(&self, point: &Point, margin: f64) -> bool {
+        self.distance(point) <= margin
+    }
+
+    fn distance(&self, point: &Point) -> f64 {
+        distance_to_polyline(point, &self.0)
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        self.0.clone()
+    }
+
+    fn strokes(&self) -> Vec<(Vec<Point>, bool)> {
+        vec![(self.0.clone(), false)]
+    }
+
+    fn svg(&self) -> String {
+        path(&self.0, false)
+    }
+}
+
+/// Several drawables shown together as the tool shape.
+#[derive(Default)]
+pub struct Overlay(Vec<Drawable>);
+
+impl From<Vec<Drawable>> for Overlay {
+    fn from(drawables: Vec<Drawable>) -> Self {
+        Self(drawables)
+    }
+}
+
+impl Draw for Overlay {
+    fn new(_bbox: &BBox) -> Self {
+        Self::default()
+    }
+
+    fn bbox(&self) -> BBox {
+        let mut bboxes = self.0.iter().map(|drawable| drawable.bbox());
+        let mut union = bboxes.next().unwrap_or_default();
+        for bbox in bboxes {
+            union.add_bbox(&bbox);
+        }
+        union
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        for drawable in &self.0 {
+            drawable.draw(context);
+        }
+    }
+}
+
+/// Drawable mirrored and then turned by `angle` radians around the center of its box.
+pub struct Transformed {
+    drawable: Drawable,
+    angle: f64,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl Transformed {
+    pub fn wrap(drawable: Drawable, angle: f64, flip_x: bool, flip_y: bool) -> Drawable {
+        if angle == 0.0 && !flip_x && !flip_y {
+            drawable
+        } else {
+            Box::new(Self {
+                drawable,
+                angle,
+                flip_x,
+                flip_y,
+            })
+        }
+    }
+
+    fn scale(&self) -> Point {
+        (
+            if self.flip_x { -1.0 } else { 1.0 },
+            if self.flip_y { -1.0 } else { 1.0 },
+        )
+    }
+
+    /// Turns and mirrors the context around the center of the drawable.
+    fn apply(&self, context: &CanvasRenderingContext2d) {
+        let (x, y) = self.drawable.bbox().center();
+        let (scale_x, scale_y) = self.scale();
+        context.translate(x, y).unwrap();
+        context.rotate(self.angle).unwrap();
+        context.scale(scale_x, scale_y).unwrap();
+        context.translate(-x, -y).unwrap();
+    }
+
+    /// Same transform as `apply`, as an SVG attribute.
+    fn svg_transform(&self) -> String {
+        let (x, y) = self.drawable.bbox().center();
+        let (scale_x, scale_y) = self.scale();
+        format!(
+            "translate({} {}) rotate({}) scale({scale_x} {scale_y}) translate({} {})",
+            number(x),
+            number(y),
+            number(self.angle.to_degrees()),
+            number(-x),
+            number(-y),
+        )
+    }
+
+    /// Mirrors the point by `scale` and then turns it by `angle` radians, both around `center`.
+    pub fn transform_around(
+        (x, y): &Point,
+        center: &Point,
+        angle: f64,
+        (scale_x, scale_y): Point,
+    ) -> Point {
+        let point = (
+            center.0 + (x - center.0) * scale_x,
+            center.1 + (y - center.1) * scale_y,
+        );
+        rotate_point(&point, center, angle)
+    }
+
+    /// Inverse of `transform_around`.
+    pub fn untransform_around(
+        point: &Point,
+        center: &Point,
+        angle: f64,
+        (scale_x, scale_y): Point,
+    ) -> Point {
+        let (x, y) = rotate_point(point, center, -angle);
+        (
+            center.0 + (x - center.0) * scale_x,
+            center.1 + (y - center.1) * scale_y,
+        )
+    }
+
+    /// Maps a point of the untransformed drawable onto the canvas.
+    fn transform(&self, point: &Point) -> Point {
+        let center = self.drawable.bbox().center();
+        Self::transform_around(point, &center, self.angle, self.scale())
+    }
+
+    /// Maps a point on the canvas back onto the untransformed drawable.
+    fn untransform(&self, point: &Point) -> Point {
+        let center = self.drawable.bbox().center();
+        Self::untransform_around(point, &center, self.angle, self.scale())
+    }
+}
+
+impl Draw for Transformed {
+    fn new(bbox: &BBox) -> Self {
+        Self {
+            drawable: Box::new(Rectangle::new(bbox)),
+            angle: 0.0,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+
+    /// Axis aligned box around the rotated outline, tighter than around the rotated frame.
+    fn bbox(&self) -> BBox {
+        let outline = self.outline();
+        if outline.is_empty() {
+            let corners = self.drawable.bbox().corners();
+            return BBox::around(&corners.map(|corner| self.transform(&corner)));
+        }
+        BBox::around(&outline)
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        context.save();
+        self.apply(context);
+        self.drawable.draw(context);
+        context.restore();
+    }
+
+    fn contains(&self, point: &Point, margin: f64) -> bool {
+        self.drawable.contains(&self.untransform(point), margin)
+    }
+
+    fn distance(&self, point: &Point) -> f64 {
+        // mirroring and rotation keep distances
+        self.drawable.distance(&self.untransform(point))
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        self.drawable
+            .outline()
+            .iter()
+            .map(|point| self.transform(point))
+            .collect()
+    }
+
+    /// Patterns turn and mirror along with the shape.
+    fn fill(&self, context: &CanvasRenderingContext2d, color: &str, style: &FillStyle) {
+        context.save();
+        self.apply(context);
+        self.drawable.fill(context, color, style);
+        context.restore();
+    }
+
+    fn svg(&self) -> String {
+        format!(
+            r#"<g transform="{}">{}</g>"#,
+            self.svg_transform(),
+            self.drawable.svg()
+        )
+    }
+
+    fn svg_fill(&self, paint: &str, color: &str, style: &FillStyle) -> String {
+        format!(
+            r#"<g transform="{}">{}</g>"#,
+            self.svg_transform(),
+            self.drawable.svg_fill(paint, color, style)
+        )
+    }
+
+    fn strokes(&self) -> Vec<(Vec<Point>, bool)> {
+        self.drawable
+            .strokes()
+            .into_iter()
+            .map(|(points, closed)| (points.iter().map(|x| self.transform(x)).collect(), closed))
+            .collect()
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct Ellipse {
     center_x: f64,
@@ -191,6 +745,8 @@ impl Draw for Ellipse {
     }
 
     fn draw(&self, context: &CanvasRenderingContext2d) {
+        // start a new sub path, else it is joined to the previous one
+        context.move_to(self.center_x + self.radius_x, self.center_y);
         context
             .ellipse(
                 self.center_x,
@@ -214,10 +770,36 @@ impl Draw for Ellipse {
         }
         (dx * dx) / (rx * rx) + (dy * dy) / (ry * ry) <= 1.0
     }
+
+    fn svg(&self) -> String {
+        format!(
+            r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}"/>"#,
+            number(self.center_x),
+            number(self.center_y),
+            number(self.radius_x),
+            number(self.radius_y),
+        )
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        (0..Self::OUTLINE_POINTS)
+            .map(|index| {
+                let angle = std::f64::consts::TAU * index as f64 / Self::OUTLINE_POINTS as f64;
+                (
+                    self.center_x + self.radius_x * angle.cos(),
+                    self.center_y + self.radius_y * angle.sin(),
+                )
+            })
+            .collect()
+    }
+}
+
+impl Ellipse {
+    const OUTLINE_POINTS: usize = 32;
 }
 
 impl ShapeToolDetails for Ellipse {
-    fn shape_type() -> ShapeType {
+    fn shape_type(_app_state: &AppState) -> ShapeType {
         ShapeType::Ellipse
     }
 
@@ -230,12 +812,30 @@ impl ShapeToolDetails for Ellipse {
     }
 }
 
-#[derive(Default, Clone, Deserialize, Serialize)]
+#[derive(Default, Clone, Display, Deserialize, Serialize)]
 #[non_exhaustive]
 pub enum ShapeType {
     #[default]
     Rectangle,
     Ellipse,
+    Triangle,
+    Diamond,
+    Polygon {
+        sides: u32,
+    },
+    Star {
+        points: u32,
+        inner_ratio: f64,
+    },
+    /// stroke through points relative to the box, from 0 to 1 on each axis
+    Freehand(Vec<Point>),
+    Connector(Connector),
+    /// curves through nodes relative to the box, like `Freehand`
+    Path(BezierPath),
+    /// picture stored once in the document under its content hash
+    Image {
+        hash: String,
+    },
 }
 
 impl ShapeType {
@@ -243,8 +843,52 @@ impl ShapeType {
         match self {
             Self::Ellipse => Box::new(Ellipse::new(bbox)),
             Self::Rectangle => Box::new(Rectangle::new(bbox)),
+            Self::Triangle => Box::new(Triangle::new(bbox)),
+            Self::Diamond => Box::new(Diamond::new(bbox)),
+            Self::Polygon { sides } => Box::new(RegularPolygon::with_sides(bbox, *sides)),
+            Self::Star {
+                points,
+                inner_ratio,
+            } => Box::new(Star::with_points(bbox, *points, *inner_ratio)),
+            Self::Freehand(points) => Box::new(Polyline::from(
+                points
+                    .iter()
+                    .map(|point| bbox.absolute(point))
+                    .collect::<Vec<Point>>(),
+            )),
+            Self::Connector(connector) => Box::new(connector.get_drawable(bbox)),
+            Self::Path(path) => Box::new(path.absolute(bbox)),
+            Self::Image { hash } => Box::new(Picture::of(hash, bbox)),
         }
     }
+
+    pub fn button_icon(&self) -> &'static str {
+        match self {
+            Self::Ellipse => Ellipse::default().button_icon(),
+            Self::Rectangle => Rectangle::default().button_icon(),
+            Self::Triangle => Triangle::default().button_icon(),
+            Self::Diamond => Diamond::default().button_icon(),
+            Self::Polygon { .. } => RegularPolygon::default().button_icon(),
+            Self::Star { .. } => Star::default().button_icon(),
+            Self::Freehand(_) => Pencil::default().button_icon(),
+            Self::Connector(_) => Connect::default().button_icon(),
+            Self::Path(_) => Pen::default().button_icon(),
+            Self::Image { .. } => "ti-photo",
+        }
+    }
+
+    /// Freehand stroke through the points along with its box.
+    pub fn freehand(points: &[Point]) -> (BBox, Self) {
+        let bbox = BBox::around(points);
+        let points = points.iter().map(|point| bbox.relative(point)).collect();
+        (bbox, Self::Freehand(points))
+    }
+
+    /// Path along with the box around its curves.
+    pub fn path(path: &BezierPath) -> (BBox, Self) {
+        let bbox = path.extent();
+        (bbox.clone(), Self::Path(path.relative(&bbox)))
+    }
 }
 
 #[derive(Default, Clone, Deserialize, Serialize)]
@@ -255,6 +899,35 @@ pub struct Shape {
     version: Version,
     color: Color,
     bg_color: Option<BackgroundColor>,
+    /// clockwise, in radians around the center of `bbox`
+    #[serde(default)]
+    rotation: f64,
+    /// mirrored horizontally before rotation
+    #[serde(default)]
+    flip_x: bool,
+    /// mirrored vertically before rotation
+    #[serde(default)]
+    flip_y: bool,
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    locked: bool,
+    /// rounds the corners of rectangles
+    #[serde(default)]
+    corner_radius: CornerRadius,
+    #[serde(default)]
+    style: RenderStyle,
+    #[serde(default)]
+    stroke_width: StrokeWidth,
+    #[serde(default)]
+    dash: Dash,
+    #[serde(default)]
+    fill: FillStyle,
+    #[serde(default)]
+    effects: Effects,
+    /// preset the style comes from, restyled whenever the preset is updated
+    #[serde(default)]
+    preset: Option<String>,
 }
 
 impl PartialEq for Shape {
@@ -277,15 +950,157 @@ impl Shape {
             version: Version::default(),
             color,
             bg_color,
+            rotation: 0.0,
+            flip_x: false,
+            flip_y: false,
+            hidden: false,
+            locked: false,
+            corner_radius: CornerRadius::default(),
+            style: RenderStyle::default(),
+            stroke_width: StrokeWidth::default(),
+            dash: Dash::default(),
+            fill: FillStyle::default(),
+            effects: Effects::default(),
+            preset: None,
         }
     }
 
+    /// Freehand stroke through the points, styled like this shape.
+    pub fn restroke(&self, points: &[Point]) -> Self {
+        let (bbox, name) = ShapeType::freehand(points);
+        let mut shape = Self::new(&bbox, name, self.color.clone(), self.bg_color.clone());
+        shape.style = self.style;
+        shape.stroke_width = self.stroke_width;
+        shape.dash = self.dash;
+        shape.fill = self.fill.clone();
+        shape.effects = self.effects.clone();
+        shape.preset = self.preset.clone();
+        shape
+    }
+
     pub fn set_color(&mut self, color: Color) {
         self.color = color.clone();
+        self.preset = None;
+    }
+
+    pub fn get_color(&self) -> &Color {
+        &self.color
+    }
+
+    pub fn get_bg_color(&self) -> &Option<BackgroundColor> {
+        &self.bg_color
+    }
+
+    pub fn get_fill(&self) -> &FillStyle {
+        &self.fill
+    }
+
+    pub fn get_effects(&self) -> &Effects {
+        &self.effects
+    }
+
+    pub fn set_effects(&mut self, effects: Effects) {
+        self.effects = effects;
+        self.preset = None;
+        self.version.increment();
+    }
+
+    pub fn modify_effects(&mut self, modification: impl Fn(&mut Effects)) {
+        modification(&mut self.effects);
+        self.preset = None;
+        self.version.increment();
+    }
+
+    pub fn get_preset(&self) -> Option<&String> {
+        self.preset.as_ref()
+    }
+
+    /// Look of the shape, apart from its geometry.
+    pub fn get_shape_style(&self) -> ShapeStyle {
+        ShapeStyle {
+            color: self.color.clone(),
+            bg_color: self.bg_color.clone(),
+            fill: self.fill.clone(),
+            render_style: self.style,
+            stroke_width: self.stroke_width,
+            dash: self.dash,
+            effects: self.effects.clone(),
+        }
+    }
+
+    pub fn set_shape_style(&mut self, style: &ShapeStyle) {
+        self.color = style.color.clone();
+        self.bg_color = style.bg_color.clone();
+        self.fill = style.fill.clone();
+        self.style = style.render_style;
+        self.stroke_width = style.stroke_width;
+        self.dash = style.dash;
+        self.effects = style.effects.clone();
+        self.version.increment();
+    }
+
+    /// Takes the style of the preset and follows its updates, until the style is edited.
+    pub fn link_preset(&mut self, name: &str, style: &ShapeStyle) {
+        self.set_shape_style(style);
+        self.preset = Some(name.to_string());
+    }
+
+    pub fn unlink_preset(&mut self) {
+        self.preset = None;
+    }
+
+    /// Box of everything drawn for the shape, its effects included.
+    pub fn bounds(&self) -> BBox {
+        self.effects.bounds(&self.bbox())
+    }
+
+    /// Whether the inside of the shape is painted.
+    pub fn is_filled(&self) -> bool {
+        self.bg_color.is_some() && !self.fill.is_none()
+    }
+
+    pub fn set_fill(&mut self, fill: FillStyle) {
+        self.fill = fill;
+        self.preset = None;
+        self.version.increment();
+    }
+
+    pub fn modify_fill(&mut self, modification: impl Fn(&mut FillStyle)) {
+        modification(&mut self.fill);
+        self.preset = None;
+        self.version.increment();
     }
 
     pub fn set_bg_color(&mut self, bg_color: Option<BackgroundColor>) {
         self.bg_color = bg_color.clone();
+        self.preset = None;
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden;
+        self.version.increment();
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+        self.version.increment();
+    }
+
+    /// Hidden and locked shapes are skipped by hit tests.
+    pub fn is_interactive(&self) -> bool {
+        !self.hidden && !self.locked
+    }
+
+    pub fn get_shape_type(&self) -> &ShapeType {
+        &self.name
     }
 
     pub fn get_id(&self) -> &Id {
@@ -296,31 +1111,213 @@ impl Shape {
         &self.version
     }
 
-    pub fn bbox(&self) -> BBox {
+    pub fn get_rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    /// Box of the shape before rotation.
+    pub fn frame(&self) -> BBox {
         self.bbox.clone()
     }
 
+    /// Axis aligned box around the (rotated) shape.
+    pub fn bbox(&self) -> BBox {
+        self.get_drawable().bbox()
+    }
+
+    /// Replaces the route of a connector, dropping any rotation or mirroring.
+    pub fn set_connector(&mut self, bbox: BBox, connector: Connector) {
+        self.bbox = bbox;
+        self.rotation = 0.0;
+        self.flip_x = false;
+        self.flip_y = false;
+        self.version.increment();
+        self.name = ShapeType::Connector(connector.routed_at(&self.version));
+    }
+
+    /// Changes the routing of a connector, it is routed again on the next update.
+    pub fn set_routing(&mut self, routing: Routing) {
+        if let ShapeType::Connector(connector) = &mut self.name {
+            connector.routing = routing;
+            self.version.increment();
+        }
+    }
+
+    /// Nodes of a path placed in the untransformed frame.
+    pub fn get_path(&self) -> Option<BezierPath> {
+        match &self.name {
+            ShapeType::Path(path) => Some(path.absolute(&self.bbox)),
+            _ => None,
+        }
+    }
+
+    /// Replaces the nodes of a path, given in the untransformed frame, keeping the unchanged
+    /// parts in place on the canvas.
+    pub fn set_path(&mut self, path: &BezierPath) {
+        let (bbox, name) = ShapeType::path(path);
+        // the frame is rotated and mirrored around its center, which moves with the new box
+        let (center_x, center_y) = self.bbox.center();
+        let (x, y) = bbox.center();
+        let opposite = (2.0 * center_x - x, 2.0 * center_y - y);
+        let (transformed_x, transformed_y) = self.transform_point(&opposite);
+        self.bbox = bbox.translate(opposite.0 - transformed_x, opposite.1 - transformed_y);
+        self.name = name;
+        self.version.increment();
+    }
+
+    pub fn get_style(&self) -> RenderStyle {
+        self.style
+    }
+
+    pub fn set_style(&mut self, style: RenderStyle) {
+        self.style = style;
+        self.preset = None;
+        self.version.increment();
+    }
+
+    pub fn get_stroke_width(&self) -> StrokeWidth {
+        self.stroke_width
+    }
+
+    pub fn set_stroke_width(&mut self, stroke_width: StrokeWidth) {
+        self.stroke_width = stroke_width;
+        self.preset = None;
+        self.version.increment();
+    }
+
+    pub fn get_dash(&self) -> Dash {
+        self.dash
+    }
+
+    pub fn set_dash(&mut self, dash: Dash) {
+        self.dash = dash;
+        self.preset = None;
+        self.version.increment();
+    }
+
+    pub fn get_corner_radius(&self) -> &CornerRadius {
+        &self.corner_radius
+    }
+
+    pub fn set_corner_radius(&mut self, corner_radius: CornerRadius) {
+        self.corner_radius = corner_radius;
+        self.version.increment();
+    }
+
+    /// Maps a point of the untransformed frame onto the canvas.
+    pub fn transform_point(&self, point: &Point) -> Point {
+        Transformed::transform_around(point, &self.bbox.center(), self.rotation, self.scale())
+    }
+
+    /// Maps a point on the canvas back onto the untransformed frame.
+    pub fn untransform_point(&self, point: &Point) -> Point {
+        Transformed::untransform_around(point, &self.bbox.center(), self.rotation, self.scale())
+    }
+
+    fn scale(&self) -> Point {
+        (
+            if self.flip_x { -1.0 } else { 1.0 },
+            if self.flip_y { -1.0 } else { 1.0 },
+        )
+    }
+
     pub fn get_drawable(&self) -> Drawable {
-        self.name.get_drawable(&self.bbox)
+        let drawable: Drawable = match self.name {
+            ShapeType::Rectangle => Box::new(Rectangle::rounded(
+                &self.bbox,
+                self.corner_radius.resolve(&self.bbox),
+            )),
+            _ => self.name.get_drawable(&self.bbox),
+        };
+        Transformed::wrap(drawable, self.rotation, self.flip_x, self.flip_y)
+    }
+
+    /// Drawable as painted, hand drawn in the sketchy style, geometry uses `get_drawable`.
+    pub fn get_rendered(&self) -> Drawable {
+        let drawable = self.get_drawable();
+        match self.style {
+            RenderStyle::Sketchy if !self.is_image() => Sketch::wrap(drawable, &self.id),
+            _ => drawable,
+        }
     }
 
     pub fn isin(&self, bbox: &BBox) -> bool {
         self.get_drawable().isin(bbox)
     }
 
+    /// Filled shapes and images are hit anywhere inside, unfilled ones only near their stroke.
     pub fn contains(&self, point: &Point, margin: f64) -> bool {
-        self.get_drawable().contains(point, margin)
-    }
-
-    // pub fn resize_to_bbox(&mut self, bbox: &BBox) -> bool {
-    //     if &self.bbox() != bbox {
-    //         self.bbox = bbox.clone();
-    //         self.version.increment();
-    //         true
-    //     } else {
-    //         false
-    //     }
-    // }
+        let drawable = self.get_drawable();
+        match self.is_filled() || self.is_image() {
+            true => drawable.contains(point, margin),
+            false => drawable.distance(point) <= margin + self.stroke_width.width() / 2.0,
+        }
+    }
+
+    /// Images keep their aspect ratio when resized.
+    pub fn is_image(&self) -> bool {
+        matches!(self.name, ShapeType::Image { .. })
+    }
+
+    pub fn intersects(&self, bbox: &BBox) -> bool {
+        self.get_drawable().intersects(bbox)
+    }
+
+    /// Whether the box, or else the outline, of the shape lies within the polygon.
+    pub fn in_polygon(&self, polygon: &[Point]) -> bool {
+        let inside = |points: &[Point]| points.iter().all(|x| point_in_polygon(x, polygon));
+        inside(&self.bbox().corners()) || inside(&self.get_drawable().outline())
+    }
+
+    /// Whether any part of the outline of the shape lies within the polygon or crosses it.
+    pub fn touches_polygon(&self, polygon: &[Point]) -> bool {
+        let drawable = self.get_drawable();
+        let outline = drawable.outline();
+        if outline.iter().any(|x| point_in_polygon(x, polygon))
+            || polygon.iter().any(|x| point_in_polygon(x, &outline))
+        {
+            return true;
+        }
+        // a thin polygon may cut across an edge with no point of either inside the other
+        let sides = edges(polygon, true);
+        drawable.strokes().iter().any(|(points, closed)| {
+            edges(points, *closed).iter().any(|(start, end)| {
+                sides
+                    .iter()
+                    .any(|(from, to)| segments_intersect(start, end, from, to))
+            })
+        })
+    }
+
+    pub fn rotate_to(&mut self, frame: &BBox, rotation: f64) {
+        self.bbox = frame.clone();
+        self.rotation = rotation;
+        self.version.increment();
+    }
+
+    /// Mirrors the shape about the vertical (`horizontal`) or horizontal line through `center`.
+    pub fn flip(&mut self, horizontal: bool, (x, y): &Point) {
+        let (center_x, center_y) = self.bbox.center();
+        if horizontal {
+            self.bbox = self.bbox.translate(2.0 * (x - center_x), 0.0);
+            self.flip_x = !self.flip_x;
+        } else {
+            self.bbox = self.bbox.translate(0.0, 2.0 * (y - center_y));
+            self.flip_y = !self.flip_y;
+        }
+        self.rotation = (-self.rotation).rem_euclid(std::f64::consts::TAU);
+        self.version.increment();
+    }
+
+    pub fn resize_to_bbox(&mut self, bbox: &BBox) -> bool {
+        if &self.bbox != bbox {
+            self.bbox = bbox.clone();
+            self.version.increment();
+            true
+        } else {
+            false
+        }
+    }
 }
 
 impl Eq for Shape {}
@@ -329,29 +1326,67 @@ impl Eq for Shape {}
 pub struct ShapeCache(RefCell<HashMap<Id, (Version, Drawable)>>);
 
 impl ShapeCache {
-    pub fn draw_from_cache(&self, shape: &Shape, context: &CanvasRenderingContext2d) {
+    const FADED_ALPHA: f64 = 0.3;
+
+    /// room around the box of a layer for the wobble of sketches, besides the stroke
+    const LAYER_PADDING: f64 = 8.0;
+
+    pub fn draw_from_cache(&self, shape: &Shape, faded: bool, context: &CanvasRenderingContext2d) {
         context.save();
-        context.begin_path();
+        if faded {
+            context.set_global_alpha(Self::FADED_ALPHA);
+        }
         let mut binding = self.0.borrow_mut();
         let entry = &binding
             .entry(shape.get_id().clone())
             .and_modify(|(version, drawable)| {
                 if shape.version.ne(version) {
                     *version = shape.get_version().clone();
-                    *drawable = shape.get_drawable();
+                    *drawable = shape.get_rendered();
                 }
             })
-            .or_insert_with(|| (shape.get_version().clone(), shape.get_drawable()))
+            .or_insert_with(|| (shape.get_version().clone(), shape.get_rendered()))
             .1;
+        if shape.effects.is_none() {
+            Self::paint(shape, entry, context);
+        } else if let Some((layer, left, top)) = Self::layer(shape, entry) {
+            // the effects apply once to the stroke and fill together, as in the SVG export
+            shape.effects.apply(context);
+            context
+                .draw_image_with_html_canvas_element(&layer, left, top)
+                .unwrap();
+        }
+        context.restore();
+    }
+
+    /// Strokes and fills the drawable of the shape.
+    fn paint(shape: &Shape, drawable: &Drawable, context: &CanvasRenderingContext2d) {
+        context.begin_path();
         context.set_stroke_style_str(&shape.color.to_string());
-        context.set_line_width(1.5);
-        entry.draw(context);
+        context.set_line_width(shape.stroke_width.width());
+        shape.dash.apply(shape.stroke_width, context);
+        drawable.draw(context);
         context.stroke();
-        if let Some(ref color) = shape.bg_color {
-            context.set_fill_style_str(&color.to_string());
-            context.fill();
+        if let Some(ref color) = shape.bg_color
+            && !shape.fill.is_none()
+        {
+            drawable.fill(context, &color.to_string(), &shape.fill);
         }
         context.close_path();
-        context.restore();
+    }
+
+    /// Offscreen canvas with the shape painted on it, and where its top left corner goes.
+    fn layer(shape: &Shape, drawable: &Drawable) -> Option<(HtmlCanvasElement, f64, f64)> {
+        let mut bbox = drawable.bbox();
+        bbox.add_padding(Self::LAYER_PADDING + shape.stroke_width.width());
+        let (left, top) = (bbox.left.floor(), bbox.top.floor());
+        let document = web_sys::window()?.document()?;
+        let layer: HtmlCanvasElement = document.create_element("canvas").ok()?.dyn_into().ok()?;
+        layer.set_width((bbox.left + bbox.width - left).ceil() as u32);
+        layer.set_height((bbox.top + bbox.height - top).ceil() as u32);
+        let context: CanvasRenderingContext2d = layer.get_context("2d").ok()??.dyn_into().ok()?;
+        context.translate(-left, -top).ok()?;
+        Self::paint(shape, drawable, &context);
+        Some((layer, left, top))
     }
 }
@@ -0,0 +1,300 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+use web_sys::CanvasRenderingContext2d;
+use web_sys::js_sys::Array;
+
+use crate::types::events::Point;
+use crate::types::fills::{FillPattern, FillStyle};
+use crate::types::ids::Id;
+use crate::types::shapes::{BBox, Draw, Drawable, Rectangle};
+use crate::types::svg::{number, path_data};
+
+/// How the outline and fill of a shape are drawn.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Display, EnumIter, Deserialize, Serialize)]
+pub enum RenderStyle {
+    #[default]
+    Clean,
+    /// hand drawn look, with wobbly double strokes and hachure fills
+    Sketchy,
+}
+
+impl RenderStyle {
+    pub fn button_icon(&self) -> &'static str {
+        match self {
+            Self::Clean => "ti-vector",
+            Self::Sketchy => "ti-scribble",
+        }
+    }
+}
+
+/// Pseudo random numbers (splitmix64), the same for the same seed.
+struct Random(u64);
+
+impl Random {
+    /// Next number between -1 and 1.
+    fn next(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1_u64 << 53) as f64 * 2.0 - 1.0
+    }
+
+    fn offset(&mut self, (x, y): &Point, amount: f64) -> Point {
+        (x + self.next() * amount, y + self.next() * amount)
+    }
+}
+
+/// Hand drawn look of a drawable, seeded by the shape so it does not change between redraws.
+pub struct Sketch {
+    drawable: Drawable,
+    /// start of each stroke along with the control point and end of its curves
+    strokes: Vec<(Point, Vec<(Point, Point)>)>,
+    /// closed strokes, which the hachure fills
+    polygons: Vec<Vec<Point>>,
+    seed: u64,
+}
+
+impl Sketch {
+    /// largest offset of a point from where it should be
+    const ROUGHNESS: f64 = 1.5;
+    /// edges shorter than this are offset less, keeping dense curves smooth
+    const SMOOTH_LENGTH: f64 = 40.0;
+    /// bowing of an edge relative to its length, up to `MAX_BOW`
+    const BOW: f64 = 0.02;
+    const MAX_BOW: f64 = 4.0;
+    /// times each stroke is drawn
+    const PASSES: usize = 2;
+    const HACHURE_GAP: f64 = 6.0;
+    /// angle of the hachure lines, in radians from the horizontal
+    const HACHURE_ANGLE: f64 = -0.7156;
+    const HACHURE_WIDTH: f64 = 1.0;
+
+    pub fn wrap(drawable: Drawable, seed: &Id) -> Drawable {
+        let seed = seed.seed();
+        let mut random = Random(seed);
+        let mut strokes = vec![];
+        let mut polygons = vec![];
+        for (points, closed) in drawable.strokes() {
+            for _ in 0..Self::PASSES {
+                strokes.extend(Self::wobble(&points, closed, &mut random));
+            }
+            if closed {
+                polygons.push(points);
+            }
+        }
+        Box::new(Self {
+            drawable,
+            strokes,
+            polygons,
+            seed,
+        })
+    }
+
+    /// Hachure lines of the fill style, none for the styles without lines.
+    fn hachures(&self, style: &FillStyle) -> Option<Vec<(Point, Point)>> {
+        let spacing = style.spacing.max(FillStyle::MIN_SPACING) as f64;
+        let angles = match style.pattern {
+            FillPattern::Solid => vec![(Self::HACHURE_ANGLE, Self::HACHURE_GAP)],
+            FillPattern::Hatch => vec![(style.radians(), spacing)],
+            FillPattern::CrossHatch => vec![
+                (style.radians(), spacing),
+                (style.radians() + std::f64::consts::FRAC_PI_2, spacing),
+            ],
+            FillPattern::Dots | FillPattern::Linear | FillPattern::Radial | FillPattern::None => {
+                return None;
+            }
+        };
+        // seeded apart from the strokes so that changing the fill leaves them alone
+        let mut random = Random(!self.seed);
+        let lines = angles
+            .into_iter()
+            .flat_map(|(angle, gap)| {
+                let random = &mut random;
+                self.polygons
+                    .iter()
+                    .flat_map(|polygon| Self::hachure(polygon, angle, gap, random))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Some(lines)
+    }
+
+    /// Stroke through the points moved a little, with slightly bowed edges.
+    fn wobble(
+        points: &[Point],
+        closed: bool,
+        random: &mut Random,
+    ) -> Option<(Point, Vec<(Point, Point)>)> {
+        let mut points = points.to_vec();
+        if closed {
+            points.extend(points.first().cloned());
+        }
+        let length = |(x1, y1): &Point, (x2, y2): &Point| (x2 - x1).hypot(y2 - y1);
+        let jittered: Vec<Point> = (0..points.len())
+            .map(|index| {
+                let before = index.checked_sub(1).map(|previous| &points[previous]);
+                let after = points.get(index + 1);
+                let shortest = [before, after]
+                    .into_iter()
+                    .flatten()
+                    .map(|other| length(&points[index], other))
+                    .fold(Self::SMOOTH_LENGTH, f64::min);
+                let amount = Self::ROUGHNESS * shortest / Self::SMOOTH_LENGTH;
+                random.offset(&points[index], amount)
+            })
+            .collect();
+        let (start, rest) = jittered.split_first()?;
+        let mut previous = *start;
+        let edges = rest
+            .iter()
+            .map(|end| {
+                let edge = length(&previous, end);
+                let bow = random.next() * (edge * Self::BOW).min(Self::MAX_BOW);
+                let (dx, dy) = match edge > 0.0 {
+                    true => ((end.0 - previous.0) / edge, (end.1 - previous.1) / edge),
+                    false => (0.0, 0.0),
+                };
+                let control = (
+                    (previous.0 + end.0) / 2.0 - dy * bow,
+                    (previous.1 + end.1) / 2.0 + dx * bow,
+                );
+                previous = *end;
+                (control, *end)
+            })
+            .collect();
+        Some((*start, edges))
+    }
+
+    /// Parallel lines at the angle across the inside of the polygon, `gap` apart.
+    fn hachure(
+        polygon: &[Point],
+        angle: f64,
+        gap: f64,
+        random: &mut Random,
+    ) -> Vec<(Point, Point)> {
+        let (sin, cos) = angle.sin_cos();
+        // turned so that the lines are horizontal
+        let turned: Vec<Point> = polygon
+            .iter()
+            .map(|(x, y)| (x * cos + y * sin, y * cos - x * sin))
+            .collect();
+        let unturn = |(x, y): Point| (x * cos - y * sin, x * sin + y * cos);
+        let bbox = BBox::around(&turned);
+        let mut lines = vec![];
+        let mut y = bbox.top + gap / 2.0;
+        while y < bbox.bottom() {
+            let mut xs: Vec<f64> = turned
+                .iter()
+                .zip(turned.iter().cycle().skip(1))
+                .filter(|((_, y1), (_, y2))| (*y1 > y) != (*y2 > y))
+                .map(|((x1, y1), (x2, y2))| x1 + (y - y1) * (x2 - x1) / (y2 - y1))
+                .collect();
+            xs.sort_by(f64::total_cmp);
+            for pair in xs.chunks_exact(2) {
+                let start = random.offset(&unturn((pair[0], y)), Self::ROUGHNESS / 2.0);
+                let end = random.offset(&unturn((pair[1], y)), Self::ROUGHNESS / 2.0);
+                lines.push((start, end));
+            }
+            y += gap;
+        }
+        lines
+    }
+}
+
+impl Draw for Sketch {
+    fn new(bbox: &BBox) -> Self {
+        Self {
+            drawable: Box::new(Rectangle::new(bbox)),
+            strokes: vec![],
+            polygons: vec![],
+            seed: 0,
+        }
+    }
+
+    fn bbox(&self) -> BBox {
+        self.drawable.bbox()
+    }
+
+    fn draw(&self, context: &CanvasRenderingContext2d) {
+        for ((x, y), edges) in &self.strokes {
+            context.move_to(*x, *y);
+            for ((control_x, control_y), (x, y)) in edges {
+                context.quadratic_curve_to(*control_x, *control_y, *x, *y);
+            }
+        }
+    }
+
+    /// Hachure lines in the fill color, under the strokes drawn again on top.
+    fn fill(&self, context: &CanvasRenderingContext2d, color: &str, style: &FillStyle) {
+        let Some(hachures) = self.hachures(style) else {
+            return self.drawable.fill(context, color, style);
+        };
+        context.save();
+        context.begin_path();
+        for ((x1, y1), (x2, y2)) in hachures {
+            context.move_to(x1, y1);
+            context.line_to(x2, y2);
+        }
+        context.set_stroke_style_str(color);
+        context.set_line_width(Self::HACHURE_WIDTH);
+        context.set_line_dash(&Array::new()).unwrap();
+        context.stroke();
+        context.restore();
+        context.begin_path();
+        self.draw(context);
+        context.stroke();
+    }
+
+    fn isin(&self, bbox: &BBox) -> bool {
+        self.drawable.isin(bbox)
+    }
+
+    fn intersects(&self, bbox: &BBox) -> bool {
+        self.drawable.intersects(bbox)
+    }
+
+    fn contains(&self, point: &Point, margin: f64) -> bool {
+        self.drawable.contains(point, margin)
+    }
+
+    fn distance(&self, point: &Point) -> f64 {
+        self.drawable.distance(point)
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        self.drawable.outline()
+    }
+
+    fn strokes(&self) -> Vec<(Vec<Point>, bool)> {
+        self.drawable.strokes()
+    }
+
+    fn svg(&self) -> String {
+        let mut data = String::new();
+        for ((x, y), edges) in &self.strokes {
+            data.push_str(&format!("M{} {}", number(*x), number(*y)));
+            for ((control_x, control_y), (x, y)) in edges {
+                let points = [control_x, control_y, x, y].map(|value| number(*value));
+                data.push_str(&format!("Q{}", points.join(" ")));
+            }
+        }
+        format!(r#"<path d="{data}"/>"#)
+    }
+
+    fn svg_fill(&self, paint: &str, color: &str, style: &FillStyle) -> String {
+        let Some(hachures) = self.hachures(style) else {
+            return self.drawable.svg_fill(paint, color, style);
+        };
+        let data: String = hachures
+            .iter()
+            .map(|(start, end)| path_data(&[*start, *end], false))
+            .collect();
+        format!(
+            r#"<path d="{data}" stroke="{color}" stroke-width="{}" stroke-dasharray="none"/>{}"#,
+            number(Self::HACHURE_WIDTH),
+            self.svg()
+        )
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+use wasm_bindgen::JsValue;
+use web_sys::CanvasRenderingContext2d;
+use web_sys::js_sys::Array;
+
+use crate::types::svg::number;
+
+/// Thickness of the outline of a shape.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Display, EnumIter, Deserialize, Serialize)]
+pub enum StrokeWidth {
+    Thin,
+    #[default]
+    Regular,
+    Bold,
+    ExtraBold,
+}
+
+impl StrokeWidth {
+    pub fn width(&self) -> f64 {
+        match self {
+            Self::Thin => 1.0,
+            Self::Regular => 1.5,
+            Self::Bold => 3.0,
+            Self::ExtraBold => 5.0,
+        }
+    }
+}
+
+/// Pattern of the outline of a shape.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Display, EnumIter, Deserialize, Serialize)]
+pub enum Dash {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl Dash {
+    pub fn button_icon(&self) -> &'static str {
+        match self {
+            Self::Solid => "ti-line",
+            Self::Dashed => "ti-line-dashed",
+            Self::Dotted => "ti-line-dotted",
+        }
+    }
+
+    /// Lengths of the dashes and gaps, growing with the width of the line.
+    fn segments(&self, width: StrokeWidth) -> Vec<f64> {
+        let width = width.width();
+        match self {
+            Self::Solid => vec![],
+            Self::Dashed => vec![4.0 * width, 3.0 * width],
+            Self::Dotted => vec![width, 2.0 * width],
+        }
+    }
+
+    /// Sets up the context so that the lines stroked next have the pattern.
+    pub fn apply(&self, width: StrokeWidth, context: &CanvasRenderingContext2d) {
+        let segments: Array = self
+            .segments(width)
+            .into_iter()
+            .map(JsValue::from_f64)
+            .collect();
+        context.set_line_dash(&segments).unwrap();
+    }
+
+    /// Same pattern as `apply`, as an SVG attribute, empty for solid lines.
+    pub fn svg_attribute(&self, width: StrokeWidth) -> String {
+        let segments = self.segments(width);
+        if segments.is_empty() {
+            return String::new();
+        }
+        let segments: Vec<String> = segments.into_iter().map(number).collect();
+        format!(r#" stroke-dasharray="{}""#, segments.join(" "))
+    }
+}
@@ -0,0 +1,109 @@
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use crate::store::shapes::Shapes;
+use crate::types::events::Point;
+
+/// room left around the shapes in the exported image
+const MARGIN: f64 = 10.0;
+
+/// Number with at most two decimals, without trailing zeros.
+pub fn number(value: f64) -> String {
+    let text = format!("{value:.2}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+/// Path data through the points.
+pub fn path_data(points: &[Point], closed: bool) -> String {
+    let mut data = String::new();
+    for (index, (x, y)) in points.iter().enumerate() {
+        let command = if index == 0 { 'M' } else { 'L' };
+        data.push_str(&format!("{command}{} {}", number(*x), number(*y)));
+    }
+    if closed && !points.is_empty() {
+        data.push('Z');
+    }
+    data
+}
+
+/// Path element through the points.
+pub fn path(points: &[Point], closed: bool) -> String {
+    format!(r#"<path d="{}"/>"#, path_data(points, closed))
+}
+
+/// SVG document with the visible shapes, as they are drawn on the canvas.
+pub fn export(shapes: &Shapes) -> String {
+    let visible: Vec<_> = shapes.shapes.iter().filter(|x| !x.is_hidden()).collect();
+    let mut defs = String::new();
+    let mut body = String::new();
+    let mut bounds = None;
+    for (index, shape) in visible.iter().enumerate() {
+        let drawable = shape.get_rendered();
+        let mut bbox = shape.bounds();
+        bbox.add_padding(MARGIN);
+        bounds.get_or_insert_with(|| bbox.clone()).add_bbox(&bbox);
+        let color = shape.get_color().to_string();
+        let fill = match (shape.get_bg_color(), shape.get_fill()) {
+            (Some(bg_color), style) if !style.is_none() => {
+                let bg_color = bg_color.to_string();
+                let id = format!("fill-{index}");
+                let paint = match style.svg_paint(&id, &bg_color) {
+                    Some(pattern) => {
+                        defs.push_str(&pattern);
+                        format!("url(#{id})")
+                    }
+                    None => bg_color.clone(),
+                };
+                drawable.svg_fill(&paint, &bg_color, style)
+            }
+            _ => String::new(),
+        };
+        let id = format!("effects-{index}");
+        let filter = match shape.get_effects().svg_filter(&id, &drawable.bbox()) {
+            Some(filter) => {
+                defs.push_str(&filter);
+                format!(r#" filter="url(#{id})""#)
+            }
+            None => String::new(),
+        };
+        body.push_str(&format!(
+            r#"<g stroke="{color}" stroke-width="{}"{} fill="none"{filter}>{}{fill}</g>"#,
+            number(shape.get_stroke_width().width()),
+            shape.get_dash().svg_attribute(shape.get_stroke_width()),
+            drawable.svg(),
+        ));
+    }
+    let bounds = bounds.unwrap_or_default();
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}"><defs>{defs}</defs>{body}</svg>"#,
+        number(bounds.left),
+        number(bounds.top),
+        number(bounds.width),
+        number(bounds.height),
+        number(bounds.width),
+        number(bounds.height),
+    )
+}
+
+/// Saves the SVG document as a file.
+pub fn download(svg: &str, name: &str) {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+    let Ok(link) = document.create_element("a") else {
+        return;
+    };
+    let href = format!(
+        "data:image/svg+xml;charset=utf-8,{}",
+        web_sys::js_sys::encode_uri_component(svg)
+    );
+    let _ = link.set_attribute("href", &href);
+    let _ = link.set_attribute("download", name);
+    if let Ok(link) = link.dyn_into::<HtmlElement>() {
+        link.click();
+    }
+}
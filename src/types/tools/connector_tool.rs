@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use super::ToolAction;
+use super::select_tool::Select;
+use crate::store::AppState;
+use crate::store::shapes::Shapes;
+use crate::types::connectors::{Anchor, Anchors, Connector, Endpoint, Routing};
+use crate::types::events::{CanvasEvent, Point};
+use crate::types::shapes::{Drawable, Overlay, Shape, ShapeType};
+
+#[derive(Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConnectorOptions {
+    pub routing: Routing,
+}
+
+#[derive(Default, Clone)]
+pub struct Connect();
+
+impl Connect {
+    const MARGIN: f64 = 10.0;
+
+    /// Topmost shape a connector can attach to at the point.
+    fn get_target<'a>(point: &Point, shapes: &'a Shapes) -> Option<&'a Shape> {
+        shapes.shapes.iter().rev().find(|shape| {
+            shape.is_interactive()
+                && !matches!(shape.get_shape_type(), ShapeType::Connector(_))
+                && shape
+                    .frame()
+                    .contains(&shape.untransform_point(point), Self::MARGIN)
+        })
+    }
+
+    fn get_endpoint(point: &Point, shapes: &Shapes) -> Endpoint {
+        match Self::get_target(point, shapes) {
+            Some(shape) => Endpoint::bound(shape, Anchor::at(shape, point, Self::MARGIN)),
+            None => Endpoint::Free(*point),
+        }
+    }
+
+    /// Anchors of the shape under the point, if any.
+    fn get_anchors(point: &Point, shapes: &Shapes) -> Option<Drawable> {
+        Self::get_target(point, shapes).map(|shape| Box::new(Anchors::of(shape)) as Drawable)
+    }
+
+    fn get_connector(start: &Point, end: &Point, app_state: &AppState) -> Option<Shape> {
+        let shapes = app_state.get_shapes();
+        let (bbox, connector) = Connector::new(
+            Self::get_endpoint(start, shapes),
+            Self::get_endpoint(end, shapes),
+            app_state.get_connector_options().routing,
+            &shapes.shapes,
+        )?;
+        let color = app_state.get_color().clone();
+        Some(Shape::new(
+            &bbox,
+            ShapeType::Connector(connector),
+            color,
+            None,
+        ))
+    }
+}
+
+impl ToolAction for Connect {
+    fn button_icon(&self) -> &'static str {
+        "ti-arrow-ramp-right"
+    }
+
+    fn button_title(&self) -> &'static str {
+        "Connector tool."
+    }
+
+    fn handle_event(
+        &mut self,
+        event: &CanvasEvent,
+        tool_shape: &mut Option<Drawable>,
+        app_state: &mut AppState,
+    ) -> bool {
+        match event {
+            CanvasEvent::SelectTool => {
+                app_state.set_pointer("crosshair");
+                true
+            }
+            CanvasEvent::DeselectTool => {
+                tool_shape.take();
+                app_state.set_pointer("default");
+                true
+            }
+            CanvasEvent::Hover(point) => {
+                *tool_shape = Self::get_anchors(point, app_state.get_shapes());
+                app_state.set_redraw();
+                false
+            }
+            CanvasEvent::DragMove((start, end), _) => {
+                let mut drawables: Vec<Drawable> = vec![];
+                drawables.extend(Self::get_anchors(end, app_state.get_shapes()));
+                if let Some(shape) = Self::get_connector(start, end, app_state) {
+                    drawables.push(shape.get_rendered());
+                }
+                tool_shape.replace(Box::new(Overlay::from(drawables)));
+                app_state.set_redraw();
+                true
+            }
+            CanvasEvent::DragEnd((start, end), _) => {
+                tool_shape.take();
+                let Some(shape) = Self::get_connector(start, end, app_state) else {
+                    return false;
+                };
+                app_state.replace_selected(vec![shape.get_id().clone()]);
+                app_state.add_shape(shape);
+                app_state.set_tool(Select::default().into());
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,22 +1,150 @@
+use serde::{Deserialize, Serialize};
+
 use super::ToolAction;
 use crate::store::AppState;
 use crate::store::shapes::Shapes;
-use crate::types::events::{CanvasEvent, Point};
+use crate::types::events::{CanvasEvent, Modifiers, Point};
 use crate::types::ids::Id;
-use crate::types::shapes::Drawable;
+use crate::types::shapes::{BBox, Draw, Drawable, Ellipse, Overlay, Polyline, ShapeType};
+use crate::utils::{distance_to_polyline, resample};
+
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EraseOptions {
+    /// erase along the dragged path, cutting freehand strokes where it passes
+    pub partial: bool,
+    /// reach of the eraser around the pointer
+    #[serde(default = "EraseOptions::default_radius")]
+    pub radius: u32,
+}
+
+impl EraseOptions {
+    pub const MIN_RADIUS: u32 = 1;
+
+    fn default_radius() -> u32 {
+        10
+    }
+}
+
+impl Default for EraseOptions {
+    fn default() -> Self {
+        Self {
+            partial: false,
+            radius: Self::default_radius(),
+        }
+    }
+}
+
+/// Changes made by erasing along a path.
+#[derive(Default)]
+struct Erasure {
+    /// shapes touched by the path
+    removed: Vec<Id>,
+    /// freehand strokes cut by the path along with the pieces left of them
+    cut: Vec<(Id, Vec<Vec<Point>>)>,
+    /// parts of the strokes going away
+    erased: Vec<Vec<Point>>,
+}
 
 #[derive(Default, Clone)]
-pub struct Erase();
+pub struct Erase {
+    /// pointer positions of the current drag, none once released or cancelled
+    path: Option<Vec<Point>>,
+}
 
 impl Erase {
-    const MARGIN: f64 = 10.0;
-    fn get_selected(point: &Point, shapes: &Shapes) -> Vec<Id> {
-        shapes
-            .shapes
-            .iter()
-            .filter(|shape| shape.contains(point, Self::MARGIN))
-            .map(|shape| shape.get_id().clone())
-            .collect()
+    /// Shapes touched by the path, freehand strokes are cut instead when erasing partially.
+    fn get_erasure(path: &[Point], options: &EraseOptions, shapes: &Shapes) -> Erasure {
+        let radius = options.radius as f64;
+        let path = resample(path, radius / 2.0);
+        let mut erasure = Erasure::default();
+        for shape in shapes.shapes.iter().filter(|shape| shape.is_interactive()) {
+            let id = shape.get_id().clone();
+            if !options.partial || !matches!(shape.get_shape_type(), ShapeType::Freehand(_)) {
+                if path.iter().any(|point| shape.contains(point, radius)) {
+                    erasure.removed.push(id);
+                }
+                continue;
+            }
+            let points = resample(&shape.get_drawable().outline(), radius / 2.0);
+            let reach = radius + shape.get_stroke_width().width() / 2.0;
+            let erased: Vec<bool> = points
+                .iter()
+                .map(|point| distance_to_polyline(point, &path) <= reach)
+                .collect();
+            if !erased.contains(&true) {
+                continue;
+            }
+            let (kept, gone) = Self::split(&points, &erased);
+            erasure.erased.extend(gone);
+            match kept.is_empty() {
+                true => erasure.removed.push(id),
+                false => erasure.cut.push((id, kept)),
+            }
+        }
+        erasure
+    }
+
+    /// Runs of kept points, and runs of erased points joined to their neighbours.
+    fn split(points: &[Point], erased: &[bool]) -> (Vec<Vec<Point>>, Vec<Vec<Point>>) {
+        let (mut kept, mut gone) = (vec![], vec![]);
+        let mut start = 0;
+        while start < points.len() {
+            let flag = erased[start];
+            let end = (start..points.len())
+                .find(|index| erased[*index] != flag)
+                .unwrap_or(points.len());
+            if flag {
+                gone.push(points[start.saturating_sub(1)..(end + 1).min(points.len())].to_vec());
+            } else if end - start > 1 {
+                kept.push(points[start..end].to_vec());
+            }
+            start = end;
+        }
+        (kept, gone)
+    }
+
+    fn cursor(point: &Point, app_state: &AppState) -> Drawable {
+        let radius = app_state.get_erase_options().radius as f64;
+        let start = (point.0 - radius, point.1 - radius);
+        let corner = (point.0 + radius, point.1 + radius);
+        Box::new(Ellipse::new(&BBox::from_corner(&start, &corner)))
+    }
+
+    /// Fades the shapes about to be erased, showing the cursor and the parts of cut strokes.
+    fn preview(&self, point: &Point, app_state: &mut AppState) -> Drawable {
+        let mut drawables = vec![Self::cursor(point, app_state)];
+        if let Some(path) = &self.path {
+            let options = app_state.get_erase_options();
+            let erasure = Self::get_erasure(path, options, app_state.get_shapes());
+            drawables.extend(
+                erasure
+                    .erased
+                    .into_iter()
+                    .map(|points| Box::new(Polyline::from(points)) as Drawable),
+            );
+            app_state.set_fading(erasure.removed);
+        }
+        Box::new(Overlay::from(drawables))
+    }
+
+    fn apply(path: &[Point], app_state: &mut AppState) -> bool {
+        let options = app_state.get_erase_options();
+        let erasure = Self::get_erasure(path, options, app_state.get_shapes());
+        let changed = !erasure.removed.is_empty() || !erasure.cut.is_empty();
+        for (id, kept) in erasure.cut {
+            let Some(shape) = app_state
+                .get_shapes()
+                .shapes
+                .iter()
+                .find(|shape| shape.get_id().eq(&id))
+            else {
+                continue;
+            };
+            let pieces = kept.iter().map(|points| shape.restroke(points)).collect();
+            app_state.replace_shape(&id, pieces);
+        }
+        app_state.remove_shapes(erasure.removed);
+        changed
     }
 }
 
@@ -32,15 +160,49 @@ impl ToolAction for Erase {
     fn handle_event(
         &mut self,
         event: &CanvasEvent,
-        _tool_shape: &mut Option<Drawable>,
+        tool_shape: &mut Option<Drawable>,
         app_state: &mut AppState,
     ) -> bool {
         match event {
-            CanvasEvent::DragEnd((_, point)) | CanvasEvent::Click(point) => {
-                let shapes = Self::get_selected(point, app_state.get_shapes());
-                let changed = !shapes.is_empty();
-                app_state.remove_shapes(shapes);
-                changed
+            CanvasEvent::Hover(point) => {
+                tool_shape.replace(Self::cursor(point, app_state));
+                app_state.set_redraw();
+                false
+            }
+            CanvasEvent::PointerEventStart(point, _) => {
+                self.path = Some(vec![*point]);
+                tool_shape.replace(self.preview(point, app_state));
+                true
+            }
+            CanvasEvent::DragMove((_, end), _) => {
+                if let Some(path) = &mut self.path {
+                    path.push(*end);
+                }
+                tool_shape.replace(self.preview(end, app_state));
+                true
+            }
+            CanvasEvent::DragEnd((_, point), _) | CanvasEvent::Click(point, _) => {
+                tool_shape.replace(Self::cursor(point, app_state));
+                app_state.set_fading(vec![]);
+                let Some(mut path) = self.path.take() else {
+                    return false;
+                };
+                path.push(*point);
+                Self::apply(&path, app_state)
+            }
+            CanvasEvent::KeyPress(key, modifiers)
+                if key == "Escape" && *modifiers == Modifiers::default() && self.path.is_some() =>
+            {
+                self.path = None;
+                tool_shape.take();
+                app_state.set_fading(vec![]);
+                true
+            }
+            CanvasEvent::DeselectTool => {
+                self.path = None;
+                tool_shape.take();
+                app_state.set_fading(vec![]);
+                true
             }
             _ => false,
         }
@@ -0,0 +1,67 @@
+use super::ToolAction;
+use crate::store::AppState;
+use crate::types::events::{CanvasEvent, Point};
+use crate::types::shapes::{Drawable, Shape};
+
+/// Picks the stroke color of new shapes from a clicked shape, or its fill color with shift.
+#[derive(Default, Clone)]
+pub struct Eyedropper;
+
+impl Eyedropper {
+    const MARGIN: f64 = 10.0;
+
+    /// Topmost shape under the point.
+    fn get_hit<'a>(point: &Point, app_state: &'a AppState) -> Option<&'a Shape> {
+        app_state
+            .get_shapes()
+            .shapes
+            .iter()
+            .rev()
+            .find(|shape| !shape.is_hidden() && shape.contains(point, Self::MARGIN))
+    }
+}
+
+impl ToolAction for Eyedropper {
+    fn button_icon(&self) -> &'static str {
+        "ti-color-picker"
+    }
+
+    fn button_title(&self) -> &'static str {
+        "Eyedropper tool (shift picks the fill color)."
+    }
+
+    fn handle_event(
+        &mut self,
+        event: &CanvasEvent,
+        _tool_shape: &mut Option<Drawable>,
+        app_state: &mut AppState,
+    ) -> bool {
+        match event {
+            CanvasEvent::SelectTool => {
+                app_state.set_pointer("crosshair");
+                true
+            }
+            CanvasEvent::DeselectTool => {
+                app_state.set_pointer("default");
+                true
+            }
+            CanvasEvent::Click(point, modifiers) => {
+                let Some(shape) = Self::get_hit(point, app_state) else {
+                    return false;
+                };
+                match modifiers.shift {
+                    true => {
+                        let bg_color = shape.get_bg_color().clone();
+                        app_state.set_bg_color(bg_color);
+                    }
+                    false => {
+                        let color = shape.get_color().clone();
+                        app_state.set_color(color);
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,16 +1,26 @@
 use enum_dispatch::enum_dispatch;
 use strum_macros::{Display, EnumIter, EnumString};
 
+pub mod connector_tool;
 pub mod erase_tool;
+pub mod eyedropper_tool;
+pub mod pen_tool;
+pub mod pencil_tool;
 pub mod select_tool;
 pub mod shape_tool;
 
 use crate::store::AppState;
 use crate::types::events::CanvasEvent;
 
+use connector_tool::Connect;
 use erase_tool::Erase;
+use eyedropper_tool::Eyedropper;
+use pen_tool::Pen;
+use pencil_tool::Pencil;
 use select_tool::Select;
-use shape_tool::{EllipseShape, RectangleShape};
+use shape_tool::{
+    DiamondShape, EllipseShape, PolygonShape, RectangleShape, StarShape, TriangleShape,
+};
 
 use crate::types::shapes::Drawable;
 
@@ -20,12 +30,20 @@ pub enum Tool {
     Select,
     RectangleShape,
     EllipseShape,
+    TriangleShape,
+    DiamondShape,
+    PolygonShape,
+    StarShape,
+    Connect,
+    Pen,
+    Pencil,
     Erase,
+    Eyedropper,
 }
 
 impl Default for Tool {
     fn default() -> Self {
-        Select {}.into()
+        Select::default().into()
     }
 }
 
@@ -0,0 +1,155 @@
+use super::ToolAction;
+use crate::store::AppState;
+use crate::types::events::{CanvasEvent, Modifiers, Point};
+use crate::types::handles::PathHandles;
+use crate::types::paths::{BezierPath, Node};
+use crate::types::shapes::{Drawable, Overlay, Shape, ShapeType};
+
+#[derive(Default, Clone)]
+pub struct Pen {
+    /// nodes of the path being drawn
+    nodes: Vec<Node>,
+    /// the pointer went down on the first node, the path closes when it goes up
+    closing: bool,
+}
+
+impl Pen {
+    const MARGIN: f64 = 10.0;
+
+    fn near((x1, y1): &Point, (x2, y2): &Point) -> bool {
+        (x1 - x2).hypot(y1 - y2) <= Self::MARGIN
+    }
+
+    /// Path drawn so far, followed by a line to the pointer when given.
+    fn get_preview(&self, pointer: Option<&Point>) -> Option<Drawable> {
+        let first = self.nodes.first()?;
+        let mut nodes = self.nodes.clone();
+        let mut closed = false;
+        match pointer {
+            Some(point) if self.nodes.len() > 1 && Self::near(point, &first.anchor) => {
+                closed = true
+            }
+            Some(point) => nodes.push(Node::corner(*point)),
+            None => {}
+        }
+        Some(Box::new(Overlay::from(vec![
+            Box::new(BezierPath::new(nodes, closed)) as Drawable,
+            Box::new(PathHandles::from(self.nodes.clone())),
+        ])))
+    }
+
+    /// Adds the path drawn so far as a shape, paths need at least two nodes.
+    fn finish(&mut self, closed: bool, app_state: &mut AppState) {
+        let nodes = std::mem::take(&mut self.nodes);
+        self.closing = false;
+        if nodes.len() < 2 {
+            return;
+        }
+        let (bbox, shape_type) = ShapeType::path(&BezierPath::new(nodes, closed));
+        let bg_color = match closed {
+            true => app_state.get_bg_color().clone(),
+            false => None,
+        };
+        let shape = Shape::new(&bbox, shape_type, app_state.get_color().clone(), bg_color);
+        app_state.add_shape(shape);
+    }
+}
+
+impl ToolAction for Pen {
+    fn button_icon(&self) -> &'static str {
+        "ti-vector-bezier"
+    }
+
+    fn button_title(&self) -> &'static str {
+        "Pen tool, drag to make smooth nodes."
+    }
+
+    fn handle_event(
+        &mut self,
+        event: &CanvasEvent,
+        tool_shape: &mut Option<Drawable>,
+        app_state: &mut AppState,
+    ) -> bool {
+        match event {
+            CanvasEvent::SelectTool => {
+                app_state.set_pointer("crosshair");
+                true
+            }
+            CanvasEvent::DeselectTool => {
+                self.finish(false, app_state);
+                tool_shape.take();
+                app_state.set_pointer("default");
+                true
+            }
+            CanvasEvent::PointerEventStart(point, _) => {
+                match self.nodes.first() {
+                    Some(first) if self.nodes.len() > 1 && Self::near(point, &first.anchor) => {
+                        self.closing = true
+                    }
+                    _ => self.nodes.push(Node::corner(*point)),
+                }
+                true
+            }
+            CanvasEvent::Hover(point) => {
+                *tool_shape = self.get_preview(Some(point));
+                app_state.set_redraw();
+                false
+            }
+            CanvasEvent::DragMove((_, end), _) | CanvasEvent::DragEnd((_, end), _) => {
+                // dragging pulls out the handles of the new node, or of the first one when closing
+                let node = match self.closing {
+                    true => self.nodes.first_mut(),
+                    false => self.nodes.last_mut(),
+                };
+                if let Some(node) = node {
+                    *node = Node::smooth(node.anchor, *end);
+                }
+                if self.closing && matches!(event, CanvasEvent::DragEnd(..)) {
+                    self.finish(true, app_state);
+                }
+                *tool_shape = self.get_preview(None);
+                app_state.set_redraw();
+                true
+            }
+            CanvasEvent::Click(..) => {
+                if self.closing {
+                    self.finish(true, app_state);
+                }
+                *tool_shape = self.get_preview(None);
+                app_state.set_redraw();
+                true
+            }
+            CanvasEvent::DoubleClick(_) => {
+                // both clicks of the double click added a node at the same spot
+                while let [.., previous, last] = self.nodes.as_slice()
+                    && last.is_corner()
+                    && Self::near(&previous.anchor, &last.anchor)
+                {
+                    self.nodes.pop();
+                }
+                self.finish(false, app_state);
+                tool_shape.take();
+                true
+            }
+            CanvasEvent::KeyPress(key, modifiers)
+                if *modifiers == Modifiers::default() && !self.nodes.is_empty() =>
+            {
+                match key.as_str() {
+                    "Enter" => self.finish(false, app_state),
+                    "Escape" => {
+                        self.nodes.clear();
+                        self.closing = false;
+                    }
+                    "Backspace" => {
+                        self.nodes.pop();
+                    }
+                    _ => return false,
+                }
+                *tool_shape = self.get_preview(None);
+                app_state.set_redraw();
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use super::ToolAction;
+use crate::store::AppState;
+use crate::types::events::{CanvasEvent, Point};
+use crate::types::shapes::{Drawable, Polyline, Shape, ShapeType};
+
+#[derive(Default, Clone)]
+pub struct Pencil {
+    /// points of the stroke being drawn
+    points: Vec<Point>,
+}
+
+impl ToolAction for Pencil {
+    fn button_icon(&self) -> &'static str {
+        "ti-pencil"
+    }
+
+    fn button_title(&self) -> &'static str {
+        "Pencil tool."
+    }
+
+    fn handle_event(
+        &mut self,
+        event: &CanvasEvent,
+        tool_shape: &mut Option<Drawable>,
+        app_state: &mut AppState,
+    ) -> bool {
+        match event {
+            CanvasEvent::SelectTool => {
+                app_state.set_pointer("crosshair");
+                true
+            }
+            CanvasEvent::DeselectTool => {
+                self.points.clear();
+                tool_shape.take();
+                app_state.set_pointer("default");
+                true
+            }
+            CanvasEvent::PointerEventStart(point, _) => {
+                self.points = vec![*point];
+                true
+            }
+            CanvasEvent::DragMove((_, end), _) => {
+                self.points.push(*end);
+                tool_shape.replace(Box::new(Polyline::from(self.points.clone())));
+                app_state.set_redraw();
+                true
+            }
+            CanvasEvent::DragEnd((_, end), _) => {
+                self.points.push(*end);
+                let (bbox, shape_type) = ShapeType::freehand(&std::mem::take(&mut self.points));
+                let shape = Shape::new(&bbox, shape_type, app_state.get_color().clone(), None);
+                app_state.add_shape(shape);
+                tool_shape.take();
+                true
+            }
+            _ => false,
+        }
+    }
+}
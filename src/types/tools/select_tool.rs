@@ -1,22 +1,456 @@
+use serde::{Deserialize, Serialize};
+
 use super::ToolAction;
 use crate::store::AppState;
 use crate::store::shapes::Shapes;
-use crate::types::events::CanvasEvent;
+use crate::types::events::{CanvasEvent, Modifiers, Point};
+use crate::types::handles::{GradientHandles, GradientPart, Handle, PathHandles, RadiusHandle};
 use crate::types::ids::Id;
-use crate::types::shapes::{BBox, Draw, Drawable, Selection};
+use crate::types::paths::{BezierPath, NodePart};
+use crate::types::shapes::{BBox, Draw, Drawable, Lasso, Selection, Shape, ShapeType};
+
+#[derive(Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SelectOptions {
+    /// freeform selection instead of rectangle, also with alt while dragging
+    pub lasso: bool,
+    /// select shapes touching the marquee instead of those inside it
+    #[serde(default)]
+    pub intersect: bool,
+}
 
 #[derive(Default, Clone)]
-pub struct Select;
+enum Action {
+    #[default]
+    Marquee,
+    Lasso(Vec<Point>),
+    Move {
+        originals: Vec<(Id, BBox)>,
+        bbox: BBox,
+    },
+    Resize {
+        handle: Handle,
+        originals: Vec<(Id, BBox)>,
+        bbox: BBox,
+    },
+    Rotate {
+        originals: Vec<(Id, BBox, f64)>,
+        center: Point,
+    },
+    Radius {
+        id: Id,
+        /// corner radius in pixels when the drag started
+        radius: f64,
+    },
+    Gradient {
+        id: Id,
+        part: GradientPart,
+    },
+    Node {
+        id: Id,
+        index: usize,
+        part: NodePart,
+        /// path in the untransformed frame when the drag started
+        path: BezierPath,
+    },
+}
+
+#[derive(Default, Clone)]
+pub struct Select {
+    /// group entered with double click, its members are selected individually
+    entered: Option<Id>,
+    action: Action,
+    /// selection when the pointer went down, combined with the new one
+    base: Vec<Id>,
+    /// modifiers when the pointer went down, shift adds and ctrl toggles
+    modifiers: Modifiers,
+}
 
 impl Select {
     const MARGIN: f64 = 10.0;
-    fn get_selected(selection: &BBox, shapes: &Shapes) -> Vec<Id> {
+    /// rotation step while holding shift
+    const ROTATION_STEP: f64 = std::f64::consts::PI / 12.0;
+
+    /// Boxes of the movable selected shapes along with the selection box.
+    fn get_selection(app_state: &AppState) -> Option<(Vec<(Id, BBox)>, BBox)> {
+        let shapes = app_state.get_shapes();
+        let bbox = shapes.bbox_of(&shapes.selected_shapes)?;
+        let originals = shapes
+            .shapes
+            .iter()
+            .filter(|shape| {
+                shape.is_interactive() && shapes.selected_shapes.contains(shape.get_id())
+            })
+            .map(|shape| (shape.get_id().clone(), shape.frame()))
+            .collect();
+        Some((originals, bbox))
+    }
+
+    fn start_action(&mut self, point: &Point, modifiers: &Modifiers, app_state: &mut AppState) {
+        self.base = app_state.get_selected().to_vec();
+        self.modifiers = *modifiers;
+        if let Some(shape) = Self::get_editing(app_state)
+            && let Some(path) = shape.get_path()
+            && let Some((index, part)) =
+                PathHandles::of(shape).and_then(|handles| handles.find(point))
+        {
+            self.action = Action::Node {
+                id: shape.get_id().clone(),
+                index,
+                part,
+                path,
+            };
+            return;
+        }
+        if let Some(shape) = Self::get_gradient_shape(app_state)
+            && let Some(part) = GradientHandles::of(shape).and_then(|handles| handles.find(point))
+        {
+            self.action = Action::Gradient {
+                id: shape.get_id().clone(),
+                part,
+            };
+            return;
+        }
+        if let Some(shape) = Self::get_radius_shape(app_state)
+            && RadiusHandle::of(shape).is_some_and(|handle| handle.contains(point))
+        {
+            self.action = Action::Radius {
+                id: shape.get_id().clone(),
+                radius: shape.get_corner_radius().resolve(&shape.frame()),
+            };
+            return;
+        }
+        if let Some((originals, bbox)) = Self::get_selection(app_state)
+            && let Some(handle) = Handle::find(&bbox, point)
+        {
+            if handle == Handle::Rotate {
+                let shapes = app_state.get_shapes();
+                let originals = shapes
+                    .shapes
+                    .iter()
+                    .filter(|shape| originals.iter().any(|(id, _)| id.eq(shape.get_id())))
+                    .map(|shape| {
+                        let id = shape.get_id().clone();
+                        (id, shape.frame(), shape.get_rotation())
+                    })
+                    .collect();
+                self.action = Action::Rotate {
+                    originals,
+                    center: handle.anchor(&bbox),
+                };
+                return;
+            }
+            self.action = Action::Resize {
+                handle,
+                originals,
+                bbox,
+            };
+            return;
+        }
+        // away from the handles, shift and ctrl add to or toggle the selection
+        if modifiers.shift || modifiers.ctrl {
+            self.action = Action::Marquee;
+            return;
+        }
+        match Self::get_hit(point, app_state.get_shapes()) {
+            Some(hit) => {
+                if !app_state.get_selected().contains(&hit) {
+                    self.select_at(point, app_state);
+                    self.base = app_state.get_selected().to_vec();
+                }
+                self.action = match Self::get_selection(app_state) {
+                    Some((originals, bbox)) => Action::Move { originals, bbox },
+                    None => Action::Marquee,
+                };
+            }
+            None => {
+                app_state.replace_selected(vec![]);
+                self.base.clear();
+                self.action = Action::Marquee;
+            }
+        }
+    }
+
+    fn apply_action(
+        &self,
+        (start, end): &(Point, Point),
+        modifiers: &Modifiers,
+        tool_shape: &mut Option<Drawable>,
+        app_state: &mut AppState,
+    ) {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        match &self.action {
+            Action::Marquee | Action::Lasso(_) => {}
+            Action::Move { originals, bbox } => {
+                let ids: Vec<Id> = originals.iter().map(|(id, _)| id.clone()).collect();
+                let (moved, guides) = app_state.snap_bbox(&bbox.translate(dx, dy), &ids, modifiers);
+                app_state.transform_shapes(originals, bbox, &moved);
+                tool_shape.replace(Box::new(guides));
+            }
+            Action::Resize {
+                handle,
+                originals,
+                bbox,
+            } => {
+                let ids: Vec<Id> = originals.iter().map(|(id, _)| id.clone()).collect();
+                let (x, y) = handle.position(bbox);
+                let (corner, guides) = app_state.snap_point(&(x + dx, y + dy), &ids, modifiers);
+                let resized = match Self::keeps_aspect(originals, app_state) {
+                    true => BBox::from_corner_like(&handle.anchor(bbox), &corner, bbox),
+                    false => BBox::from_corner(&handle.anchor(bbox), &corner),
+                };
+                app_state.transform_shapes(originals, bbox, &resized);
+                tool_shape.replace(Box::new(guides));
+            }
+            Action::Rotate { originals, center } => {
+                let angle = |(x, y): &Point| (y - center.1).atan2(x - center.0);
+                let mut delta = angle(end) - angle(start);
+                if modifiers.shift
+                    && let Some((_, _, rotation)) = originals.first()
+                {
+                    delta = ((rotation + delta) / Self::ROTATION_STEP).round()
+                        * Self::ROTATION_STEP
+                        - rotation;
+                }
+                app_state.rotate_shapes(originals, center, delta);
+            }
+            Action::Radius { id, radius } => {
+                let Some(shape) = app_state
+                    .get_shapes()
+                    .shapes
+                    .iter()
+                    .find(|x| x.get_id().eq(id))
+                else {
+                    return;
+                };
+                // drag along the diagonal of the untransformed frame
+                let ((x1, y1), (x2, y2)) =
+                    (shape.untransform_point(start), shape.untransform_point(end));
+                let frame = shape.frame();
+                let radius = (radius + (x2 - x1 + y2 - y1) / 2.0)
+                    .clamp(0.0, frame.width.min(frame.height) / 2.0);
+                let corner_radius = shape.get_corner_radius().with_pixels(radius, &frame);
+                app_state.modify_shape(id, |shape| shape.set_corner_radius(corner_radius.clone()));
+            }
+            Action::Gradient { id, part } => {
+                let Some(shape) = app_state
+                    .get_shapes()
+                    .shapes
+                    .iter()
+                    .find(|x| x.get_id().eq(id))
+                else {
+                    return;
+                };
+                // position in the unit box of the untransformed frame
+                let frame = shape.frame();
+                if frame.width <= 0.0 || frame.height <= 0.0 {
+                    return;
+                }
+                let (x, y) = shape.untransform_point(end);
+                let unit = (
+                    (x - frame.left) / frame.width,
+                    (y - frame.top) / frame.height,
+                );
+                let mut fill = shape.get_fill().clone();
+                let gradient = &mut fill.gradient;
+                match part {
+                    GradientPart::Start => gradient.aim_at(&(1.0 - unit.0, 1.0 - unit.1)),
+                    GradientPart::End => gradient.aim_at(&unit),
+                    GradientPart::Center => gradient.move_center(&unit),
+                    GradientPart::Radius => gradient.reach(&unit),
+                }
+                app_state.modify_shape(id, |shape| shape.set_fill(fill.clone()));
+            }
+            Action::Node {
+                id,
+                index,
+                part,
+                path,
+            } => {
+                let Some(shape) = app_state
+                    .get_shapes()
+                    .shapes
+                    .iter()
+                    .find(|x| x.get_id().eq(id))
+                else {
+                    return;
+                };
+                // offset in the untransformed frame, alt moves a handle on its own
+                let ((x1, y1), (x2, y2)) =
+                    (shape.untransform_point(start), shape.untransform_point(end));
+                let mut path = path.clone();
+                if let Some(node) = path.nodes.get_mut(*index) {
+                    *node = node.moved(*part, (x2 - x1, y2 - y1), !modifiers.alt);
+                }
+                app_state.modify_shape(id, |shape| shape.set_path(&path));
+            }
+        }
+    }
+
+    /// Path shown for editing, as long as it is the only selected shape.
+    fn get_editing(app_state: &AppState) -> Option<&Shape> {
+        let shapes = app_state.get_shapes();
+        let editing = shapes.editing.as_ref()?;
+        if shapes.selected_shapes.as_slice() != [editing.clone()] {
+            return None;
+        }
+        shapes
+            .shapes
+            .iter()
+            .find(|shape| shape.get_id().eq(editing))
+    }
+
+    /// Stops editing once the path is no longer the only selected shape.
+    fn check_editing(app_state: &mut AppState) {
+        if app_state.get_shapes().editing.is_some() && Self::get_editing(app_state).is_none() {
+            app_state.set_editing(None);
+        }
+    }
+
+    /// Shows the nodes of the path under the point for editing.
+    fn edit_at(&mut self, point: &Point, app_state: &mut AppState) -> bool {
+        let shapes = app_state.get_shapes();
+        let Some(hit) = Self::get_hit(point, shapes) else {
+            return false;
+        };
+        let is_path = shapes.shapes.iter().any(|shape| {
+            shape.get_id().eq(&hit) && matches!(shape.get_shape_type(), ShapeType::Path(_))
+        });
+        if !is_path {
+            return false;
+        }
+        app_state.replace_selected(vec![hit.clone()]);
+        app_state.set_editing(Some(hit));
+        true
+    }
+
+    /// Whether the shapes include an image, which must not be distorted.
+    fn keeps_aspect(originals: &[(Id, BBox)], app_state: &AppState) -> bool {
+        app_state
+            .get_shapes()
+            .shapes
+            .iter()
+            .any(|shape| shape.is_image() && originals.iter().any(|(id, _)| id.eq(shape.get_id())))
+    }
+
+    /// Single selected shape showing gradient handles.
+    fn get_gradient_shape(app_state: &AppState) -> Option<&Shape> {
+        let shapes = app_state.get_shapes();
+        let [id] = shapes.selected_shapes.as_slice() else {
+            return None;
+        };
+        shapes
+            .shapes
+            .iter()
+            .find(|shape| shape.get_id().eq(id) && GradientHandles::of(shape).is_some())
+    }
+
+    /// Single selected shape showing a corner radius handle.
+    fn get_radius_shape(app_state: &AppState) -> Option<&Shape> {
+        let shapes = app_state.get_shapes();
+        let [id] = shapes.selected_shapes.as_slice() else {
+            return None;
+        };
+        shapes
+            .shapes
+            .iter()
+            .find(|shape| shape.get_id().eq(id) && RadiusHandle::of(shape).is_some())
+    }
+
+    /// Combines the selection with the one present when the pointer went down.
+    fn combine(&self, selected: Vec<Id>) -> Vec<Id> {
+        let (base, modifiers) = (&self.base, &self.modifiers);
+        if modifiers.shift {
+            let added = selected.into_iter().filter(|id| !base.contains(id));
+            base.iter().cloned().chain(added).collect()
+        } else if modifiers.ctrl {
+            let kept = base.iter().filter(|id| !selected.contains(id)).cloned();
+            let added = selected.iter().filter(|id| !base.contains(id)).cloned();
+            kept.chain(added).collect()
+        } else {
+            selected
+        }
+    }
+
+    /// Interactive shapes inside, whole units only unless `touching` is set.
+    fn get_selected(
+        &self,
+        inside: impl Fn(&Shape) -> bool,
+        touching: bool,
+        shapes: &Shapes,
+    ) -> Vec<Id> {
+        let inside: Vec<Id> = shapes
+            .shapes
+            .iter()
+            .filter(|shape| shape.is_interactive() && inside(shape))
+            .map(|shape| shape.get_id().clone())
+            .collect();
+        let selected = shapes
+            .units(&inside, self.entered.as_ref())
+            .iter()
+            .map(|unit| shapes.leaves(unit))
+            .filter(|leaves| {
+                let mut leaves = leaves.iter();
+                match touching {
+                    true => leaves.any(|leaf| inside.contains(leaf)),
+                    false => leaves.all(|leaf| inside.contains(leaf)),
+                }
+            })
+            .flatten()
+            .collect();
+        self.combine(selected)
+    }
+
+    /// Topmost shape under the point.
+    fn get_hit(point: &Point, shapes: &Shapes) -> Option<Id> {
         shapes
             .shapes
             .iter()
-            .filter(|shape| shape.isin(selection))
+            .rev()
+            .find(|shape| shape.is_interactive() && shape.contains(point, Self::MARGIN))
             .map(|shape| shape.get_id().clone())
-            .collect()
+    }
+
+    fn select_at(&mut self, point: &Point, app_state: &mut AppState) {
+        let shapes = app_state.get_shapes();
+        let Some(hit) = Self::get_hit(point, shapes) else {
+            if !self.modifiers.shift && !self.modifiers.ctrl {
+                self.entered = None;
+            }
+            app_state.replace_selected(self.combine(vec![]));
+            return;
+        };
+        if let Some(entered) = &self.entered
+            && !shapes.is_descendant(&hit, entered)
+        {
+            self.entered = None;
+        }
+        let selected = shapes.leaves(&shapes.outermost(&hit, self.entered.as_ref()));
+        app_state.replace_selected(self.combine(selected));
+    }
+
+    fn select_in(&self, selection: &BBox, app_state: &mut AppState) {
+        let touching = app_state.get_select_options().intersect;
+        let inside = |shape: &Shape| match touching {
+            true => shape.intersects(selection),
+            false => shape.isin(selection),
+        };
+        let selected = self.get_selected(inside, touching, app_state.get_shapes());
+        app_state.replace_selected(selected);
+    }
+
+    fn enter_at(&mut self, point: &Point, app_state: &mut AppState) -> bool {
+        let shapes = app_state.get_shapes();
+        let Some(hit) = Self::get_hit(point, shapes) else {
+            return false;
+        };
+        let unit = shapes.outermost(&hit, self.entered.as_ref());
+        if shapes.get_group(&unit).is_none() {
+            return false;
+        }
+        let selected = shapes.leaves(&shapes.outermost(&hit, Some(&unit)));
+        self.entered = Some(unit);
+        app_state.replace_selected(selected);
+        true
     }
 }
 
@@ -36,55 +470,117 @@ impl ToolAction for Select {
         app_state: &mut AppState,
     ) -> bool {
         match event {
-            CanvasEvent::PointerEventStart(_) => {
-                app_state.replace_selected(vec![]);
+            CanvasEvent::PointerEventStart(point, modifiers) => {
                 tool_shape.take();
+                self.start_action(point, modifiers, app_state);
+                Self::check_editing(app_state);
+                true
+            }
+            CanvasEvent::Hover(point) => {
+                let on_radius = Self::get_radius_shape(app_state)
+                    .and_then(RadiusHandle::of)
+                    .is_some_and(|handle| handle.contains(point))
+                    || Self::get_gradient_shape(app_state)
+                        .and_then(GradientHandles::of)
+                        .is_some_and(|handles| handles.find(point).is_some())
+                    || Self::get_editing(app_state)
+                        .and_then(PathHandles::of)
+                        .is_some_and(|handles| handles.find(point).is_some());
+                let pointer = match Self::get_selection(app_state)
+                    .and_then(|(_, bbox)| Handle::find(&bbox, point))
+                {
+                    _ if on_radius => "pointer",
+                    Some(handle) => handle.pointer(),
+                    None if Self::get_hit(point, app_state.get_shapes()).is_some() => "move",
+                    None => "default",
+                };
+                app_state.set_pointer(pointer);
+                false
+            }
+            CanvasEvent::DragMove((start, end), modifiers)
+                if matches!(self.action, Action::Marquee)
+                    && (modifiers.alt || app_state.get_select_options().lasso) =>
+            {
+                self.action = Action::Lasso(vec![*start, *end]);
+                tool_shape.replace(Box::new(Lasso::from(vec![*start, *end])));
                 true
             }
-            CanvasEvent::DragMove((start, end)) => {
+            CanvasEvent::DragMove((_, end), _) if matches!(self.action, Action::Lasso(_)) => {
+                if let Action::Lasso(points) = &mut self.action {
+                    points.push(*end);
+                }
+                if let Action::Lasso(points) = &self.action {
+                    let touching = app_state.get_select_options().intersect;
+                    let inside = |shape: &Shape| match touching {
+                        true => shape.touches_polygon(points),
+                        false => shape.in_polygon(points),
+                    };
+                    let selected = self.get_selected(inside, touching, app_state.get_shapes());
+                    app_state.replace_selected(selected);
+                    tool_shape.replace(Box::new(Lasso::from(points.clone())));
+                }
+                true
+            }
+            CanvasEvent::DragEnd(..) if matches!(self.action, Action::Lasso(_)) => {
+                self.action = Action::Marquee;
+                tool_shape.take();
+                true
+            }
+            CanvasEvent::DragMove(drag, modifiers) if !matches!(self.action, Action::Marquee) => {
+                self.apply_action(drag, modifiers, tool_shape, app_state);
+                true
+            }
+            CanvasEvent::DragEnd(drag, modifiers) if !matches!(self.action, Action::Marquee) => {
+                self.apply_action(drag, modifiers, tool_shape, app_state);
+                self.action = Action::Marquee;
+                tool_shape.take();
+                true
+            }
+            CanvasEvent::DragMove((start, end), _) => {
                 let selection = BBox::from_corner(start, end);
-                app_state.replace_selected(Self::get_selected(&selection, app_state.get_shapes()));
+                self.select_in(&selection, app_state);
                 tool_shape.replace(Box::new(Selection::new(&selection)));
                 true
             }
-            CanvasEvent::DragEnd((start, end)) => {
+            CanvasEvent::DragEnd((start, end), _) => {
                 if (start.0 - end.0).powf(2.0) + (start.1 - end.1).powf(2.0)
                     <= Self::MARGIN.powf(2.0)
                 {
-                    app_state.replace_selected(
-                        app_state
-                            .get_shapes()
-                            .shapes
-                            .iter()
-                            .filter(|shape| shape.contains(start, Self::MARGIN))
-                            .map(|shape| shape.get_id().clone())
-                            .collect(),
-                    );
+                    self.select_at(start, app_state);
                     tool_shape.take();
                 } else {
-                    let selection = BBox::from_corner(start, end);
-                    app_state
-                        .replace_selected(Self::get_selected(&selection, app_state.get_shapes()));
+                    self.select_in(&BBox::from_corner(start, end), app_state);
                     tool_shape.take();
                 }
                 true
             }
+            CanvasEvent::Click(point, modifiers) => {
+                self.action = Action::Marquee;
+                self.modifiers = *modifiers;
+                self.select_at(point, app_state);
+                Self::check_editing(app_state);
+                true
+            }
+            CanvasEvent::DoubleClick(point) => {
+                self.enter_at(point, app_state) || self.edit_at(point, app_state)
+            }
+            CanvasEvent::KeyPress(key, modifiers)
+                if key == "Escape"
+                    && *modifiers == Modifiers::default()
+                    && Self::get_editing(app_state).is_some() =>
+            {
+                app_state.set_editing(None);
+                true
+            }
             CanvasEvent::DeselectTool => {
                 tool_shape.take();
+                app_state.set_editing(None);
+                self.entered = None;
+                self.action = Action::Marquee;
                 app_state.replace_selected(vec![]);
                 app_state.set_pointer("default");
                 true
             }
-            CanvasEvent::KeyPress(key) => {
-                let mut changed = false;
-                if key.eq(&"Delete") {
-                    let selected_id = app_state.get_selected().to_vec();
-                    app_state.replace_selected(vec![]);
-                    changed = !selected_id.is_empty();
-                    app_state.remove_shapes(selected_id);
-                }
-                changed
-            }
             _ => false,
         }
     }
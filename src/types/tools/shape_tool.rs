@@ -1,13 +1,69 @@
 use std::marker::PhantomData;
 
+use serde::{Deserialize, Serialize};
+
 use super::ToolAction;
 use super::select_tool::Select;
 use crate::store::AppState;
 use crate::types::events::CanvasEvent;
-use crate::types::shapes::{BBox, Draw, Drawable, Ellipse, Rectangle, Shape, ShapeType};
+use crate::types::polygons::{Diamond, RegularPolygon, Star, Triangle};
+use crate::types::shapes::{
+    BBox, CornerRadius, Draw, Drawable, Ellipse, Overlay, Rectangle, Shape, ShapeType,
+};
+
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PolygonOptions {
+    /// sides of a regular polygon
+    pub sides: u32,
+    /// outer vertices of a star
+    pub points: u32,
+    /// inner radius of a star in percent of the outer one
+    pub inner_percent: u32,
+}
+
+impl PolygonOptions {
+    pub const MIN_VERTICES: u32 = 3;
+    pub const MAX_VERTICES: u32 = 32;
+    pub const MIN_INNER_PERCENT: u32 = 10;
+    pub const MAX_INNER_PERCENT: u32 = 90;
+
+    pub fn inner_ratio(&self) -> f64 {
+        self.inner_percent as f64 / 100.0
+    }
+}
+
+impl Default for PolygonOptions {
+    fn default() -> Self {
+        Self {
+            sides: 6,
+            points: 5,
+            inner_percent: 50,
+        }
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CornerOptions {
+    /// in pixels, or in percent of the shorter side when proportional
+    pub radius: u32,
+    pub proportional: bool,
+}
+
+impl CornerOptions {
+    pub const MAX_PERCENT: u32 = 50;
+
+    pub fn corner_radius(&self) -> CornerRadius {
+        match self.proportional {
+            true => CornerRadius::Proportional(self.radius.min(Self::MAX_PERCENT) as f64 / 100.0),
+            false => CornerRadius::Absolute(self.radius as f64),
+        }
+    }
+}
 
 pub trait ShapeToolDetails {
-    fn shape_type() -> ShapeType;
+    fn shape_type(app_state: &AppState) -> ShapeType;
     fn button_icon(&self) -> &'static str;
     fn button_title(&self) -> &'static str;
 }
@@ -17,6 +73,22 @@ pub struct ShapeTool<T> {
     marker: PhantomData<T>,
 }
 
+impl<T> ShapeTool<T>
+where
+    T: ShapeToolDetails,
+{
+    fn get_shape(bbox: &BBox, app_state: &AppState) -> Shape {
+        let mut shape = Shape::new(
+            bbox,
+            T::shape_type(app_state),
+            app_state.get_color().clone(),
+            app_state.get_bg_color().clone(),
+        );
+        shape.set_corner_radius(app_state.get_corner_options().corner_radius());
+        shape
+    }
+}
+
 impl<T> ToolAction for ShapeTool<T>
 where
     T: ShapeToolDetails + Default + Draw,
@@ -44,23 +116,25 @@ where
                 app_state.set_pointer("default");
                 true
             }
-            CanvasEvent::DragMove((start, end)) => {
-                let selection = BBox::from_corner(start, end);
-                tool_shape.replace(Box::new(T::new(&selection)));
+            CanvasEvent::DragMove((start, end), modifiers) => {
+                let (start, _) = app_state.snap_point(start, &[], modifiers);
+                let (end, guides) = app_state.snap_point(end, &[], modifiers);
+                let selection = BBox::from_corner(&start, &end);
+                tool_shape.replace(Box::new(Overlay::from(vec![
+                    Self::get_shape(&selection, app_state).get_rendered(),
+                    Box::new(guides),
+                ])));
                 app_state.set_redraw();
                 true
             }
-            CanvasEvent::DragEnd((start, end)) => {
-                let shape = Shape::new(
-                    &BBox::from_corner(start, end),
-                    T::shape_type(),
-                    app_state.get_color().clone(),
-                    app_state.get_bg_color().clone(),
-                );
+            CanvasEvent::DragEnd((start, end), modifiers) => {
+                let (start, _) = app_state.snap_point(start, &[], modifiers);
+                let (end, _) = app_state.snap_point(end, &[], modifiers);
+                let shape = Self::get_shape(&BBox::from_corner(&start, &end), app_state);
                 app_state.replace_selected(vec![shape.get_id().clone()]);
                 app_state.add_shape(shape);
                 tool_shape.take();
-                app_state.set_tool(Select {}.into());
+                app_state.set_tool(Select::default().into());
                 true
             }
             _ => false,
@@ -69,3 +143,7 @@ where
 }
 pub type RectangleShape = ShapeTool<Rectangle>;
 pub type EllipseShape = ShapeTool<Ellipse>;
+pub type TriangleShape = ShapeTool<Triangle>;
+pub type DiamondShape = ShapeTool<Diamond>;
+pub type PolygonShape = ShapeTool<RegularPolygon>;
+pub type StarShape = ShapeTool<Star>;
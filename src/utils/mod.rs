@@ -1 +1,136 @@
+use crate::types::events::Point;
 
+/// Rotates the point around `center` by `angle` radians.
+pub fn rotate_point((x, y): &Point, (cx, cy): &Point, angle: f64) -> Point {
+    let (sin, cos) = angle.sin_cos();
+    let (dx, dy) = (x - cx, y - cy);
+    (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos)
+}
+
+/// Even-odd ray casting test of the point against the closed polygon.
+pub fn point_in_polygon((x, y): &Point, polygon: &[Point]) -> bool {
+    let mut inside = false;
+    let mut previous = match polygon.last() {
+        Some(point) => point,
+        None => return false,
+    };
+    for current in polygon {
+        let ((x1, y1), (x2, y2)) = (previous, current);
+        if (y1 > y) != (y2 > y) && *x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+            inside = !inside;
+        }
+        previous = current;
+    }
+    inside
+}
+
+/// Point of the segment between the other two points nearest to the point.
+pub fn nearest_on_segment((x, y): &Point, (x1, y1): &Point, (x2, y2): &Point) -> Point {
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let length = dx * dx + dy * dy;
+    let t = match length > 0.0 {
+        true => (((x - x1) * dx + (y - y1) * dy) / length).clamp(0.0, 1.0),
+        false => 0.0,
+    };
+    (x1 + t * dx, y1 + t * dy)
+}
+
+/// Distance from the point to the segment between the other two points.
+pub fn distance_to_segment(point: &Point, start: &Point, end: &Point) -> f64 {
+    let (x, y) = nearest_on_segment(point, start, end);
+    (point.0 - x).hypot(point.1 - y)
+}
+
+/// Point on the edges of the closed polygon nearest to the point.
+pub fn nearest_on_polygon(point: &Point, polygon: &[Point]) -> Option<Point> {
+    let mut previous = polygon.last()?;
+    let mut nearest = None;
+    let mut distance = f64::INFINITY;
+    for current in polygon {
+        let candidate = nearest_on_segment(point, previous, current);
+        let candidate_distance = (point.0 - candidate.0).hypot(point.1 - candidate.1);
+        if candidate_distance < distance {
+            (nearest, distance) = (Some(candidate), candidate_distance);
+        }
+        previous = current;
+    }
+    nearest
+}
+
+/// Whether the segment between the first two points crosses or touches the one between the others.
+pub fn segments_intersect(a: &Point, b: &Point, c: &Point, d: &Point) -> bool {
+    let cross = |(x1, y1): &Point, (x2, y2): &Point, (x3, y3): &Point| {
+        (x2 - x1) * (y3 - y1) - (y2 - y1) * (x3 - x1)
+    };
+    let (abc, abd) = (cross(a, b, c), cross(a, b, d));
+    let (cda, cdb) = (cross(c, d, a), cross(c, d, b));
+    if abc * abd > 0.0 || cda * cdb > 0.0 {
+        return false;
+    }
+    if abc == 0.0 && abd == 0.0 {
+        // on one line, they meet when their extents overlap
+        let overlap = |p: f64, q: f64, r: f64, s: f64| p.max(q) >= r.min(s) && r.max(s) >= p.min(q);
+        return overlap(a.0, b.0, c.0, d.0) && overlap(a.1, b.1, c.1, d.1);
+    }
+    true
+}
+
+/// Edges between consecutive points, back to the first one when `closed`.
+pub fn edges(points: &[Point], closed: bool) -> Vec<(Point, Point)> {
+    let mut edges: Vec<(Point, Point)> = points.windows(2).map(|pair| (pair[0], pair[1])).collect();
+    if closed && let (Some(first), Some(last)) = (points.first(), points.last()) {
+        edges.push((*last, *first));
+    }
+    edges
+}
+
+/// Distance from the point to the edges of the closed polygon.
+pub fn distance_to_polygon(point: &Point, polygon: &[Point]) -> f64 {
+    let Some(mut previous) = polygon.last() else {
+        return f64::INFINITY;
+    };
+    let mut distance = f64::INFINITY;
+    for current in polygon {
+        distance = distance.min(distance_to_segment(point, previous, current));
+        previous = current;
+    }
+    distance
+}
+
+/// Distance from the point to the open path through the points.
+pub fn distance_to_polyline(point: &Point, points: &[Point]) -> f64 {
+    match points {
+        [] => f64::INFINITY,
+        [(x, y)] => (point.0 - x).hypot(point.1 - y),
+        _ => points
+            .windows(2)
+            .map(|segment| distance_to_segment(point, &segment[0], &segment[1]))
+            .fold(f64::INFINITY, f64::min),
+    }
+}
+
+/// Inserts points along the path so that no segment is longer than `step`.
+pub fn resample(points: &[Point], step: f64) -> Vec<Point> {
+    let mut resampled: Vec<Point> = points.first().into_iter().cloned().collect();
+    for segment in points.windows(2) {
+        let ((x1, y1), (x2, y2)) = (segment[0], segment[1]);
+        let count = ((x2 - x1).hypot(y2 - y1) / step).ceil().max(1.0) as usize;
+        resampled.extend((1..=count).map(|index| {
+            let t = index as f64 / count as f64;
+            (x1 + (x2 - x1) * t, y1 + (y2 - y1) * t)
+        }));
+    }
+    resampled
+}
+
+/// Stable 64 bit FNV-1a hash of the text.
+pub fn fnv_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Hash of the content in hexadecimal, the same across sessions.
+pub fn content_hash(text: &str) -> String {
+    format!("{:016x}", fnv_hash(text))
+}
@@ -1,4 +1,5 @@
 use crate::views::the_canvas::TheCanvas;
+use crate::views::the_layers::TheLayers;
 use crate::views::the_sidebar::TheSidebar;
 use crate::views::the_toolbar::TheToolbar;
 use yew::prelude::*;
@@ -10,6 +11,7 @@ pub fn app() -> Html {
             <TheToolbar />
             <TheCanvas />
             <TheSidebar />
+            <TheLayers />
         </div>
     }
 }
@@ -1,4 +1,5 @@
 pub mod app;
 pub mod the_canvas;
+pub mod the_layers;
 pub mod the_sidebar;
 pub mod the_toolbar;
@@ -1,11 +1,18 @@
 use wasm_bindgen::{JsCast, JsValue};
-use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use web_sys::{CanvasRenderingContext2d, ClipboardEvent, HtmlCanvasElement};
 use yew::prelude::*;
 use yewdux::prelude::*;
 
+use crate::components::action_bar::ActionBar;
+use crate::components::context_menu::ContextMenu;
 use crate::store::AppState;
+use crate::store::library::Library;
 use crate::store::shapes::Shapes;
-use crate::types::events::CanvasEvent;
+use crate::types::events::{CanvasEvent, Modifiers};
+use crate::types::grid::Grid;
+use crate::types::handles::{GradientHandles, Handles, PathHandles, RadiusHandle};
+use crate::types::images;
+use crate::types::keymap::KeyChord;
 use crate::types::shapes::{Draw, Drawable, Selection, ShapeCache};
 use crate::types::tools::{Tool, ToolAction};
 
@@ -48,7 +55,7 @@ impl EventHandler {
         }
     }
 
-    fn refresh_canvas(&self, shapes: &Shapes) {
+    fn refresh_canvas(&self, shapes: &Shapes, grid: &Grid) {
         let canvas = self.get_canvas();
         canvas.set_width(canvas.client_width().abs_diff(0));
         canvas.set_height(canvas.client_height().abs_diff(0));
@@ -59,25 +66,59 @@ impl EventHandler {
             .dyn_into()
             .unwrap();
         context.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
-        let mut selections = vec![];
-        for shape in &shapes.shapes {
-            self.shape_cache.draw_from_cache(shape, &context);
+        grid.draw(&context, canvas.width() as f64, canvas.height() as f64);
+        for shape in shapes.shapes.iter().filter(|shape| !shape.is_hidden()) {
+            let faded = shapes.fading.contains(shape.get_id());
+            self.shape_cache.draw_from_cache(shape, faded, &context);
             if shapes.selected_shapes.contains(shape.get_id()) {
                 let mut padded_bbox = shape.bbox();
                 padded_bbox.add_padding(PADDING);
-                selections.push(Box::new(Selection::new(&padded_bbox)));
+                Selection::new(&padded_bbox).draw(&context);
             }
         }
-        for shape in &selections {
-            shape.draw(&context);
+        for group in &shapes.groups {
+            let leaves = shapes.leaves(&group.id);
+            if leaves.iter().all(|id| shapes.selected_shapes.contains(id))
+                && let Some(mut group_bbox) = shapes.bbox_of(&leaves)
+            {
+                group_bbox.add_padding(PADDING + PADDING);
+                Selection::new(&group_bbox).draw(&context);
+            }
         }
-        if let Some((first, rest)) = selections.split_first() {
-            let mut group = first.bbox();
-            for shape in rest {
-                group.add_bbox(&shape.bbox());
-                shape.draw(&context);
+        if let Some(mut group_bbox) = shapes.bbox_of(&shapes.selected_shapes) {
+            Handles::new(&group_bbox).draw(&context);
+            if let [id] = shapes.selected_shapes.as_slice()
+                && let Some(handle) = shapes
+                    .shapes
+                    .iter()
+                    .find(|shape| shape.get_id().eq(id))
+                    .and_then(RadiusHandle::of)
+            {
+                handle.draw(&context);
+            }
+            if let [id] = shapes.selected_shapes.as_slice()
+                && let Some(handles) = shapes
+                    .shapes
+                    .iter()
+                    .find(|shape| shape.get_id().eq(id))
+                    .and_then(GradientHandles::of)
+            {
+                handles.draw(&context);
+            }
+            if let [id] = shapes.selected_shapes.as_slice()
+                && shapes.editing.as_ref() == Some(id)
+                && let Some(handles) = shapes
+                    .shapes
+                    .iter()
+                    .find(|shape| shape.get_id().eq(id))
+                    .and_then(PathHandles::of)
+            {
+                handles.draw(&context);
+            }
+            if shapes.selected_shapes.len() > 1 {
+                group_bbox.add_padding(PADDING);
+                Selection::new(&group_bbox).draw(&context);
             }
-            Selection::new(&group).draw(&context);
         }
         if let Some(shape) = &self.shape {
             context.save();
@@ -94,18 +135,41 @@ impl EventHandler {
         }
     }
 
-    fn get_event_canvas_postion(canvas: &HtmlCanvasElement, event: &PointerEvent) -> (f64, f64) {
+    fn get_event_canvas_postion(canvas: &HtmlCanvasElement, event: &MouseEvent) -> (f64, f64) {
         let rect = canvas.get_bounding_client_rect();
         let x = (event.client_x() as f64 - rect.left()) * (canvas.width() as f64 / rect.width());
         let y = (event.client_y() as f64 - rect.top()) * (canvas.height() as f64 / rect.height());
         (x, y)
     }
 
+    fn get_event_modifiers(event: &MouseEvent) -> Modifiers {
+        Modifiers {
+            ctrl: event.ctrl_key() || event.meta_key(),
+            shift: event.shift_key(),
+            alt: event.alt_key(),
+        }
+    }
+
     pub fn handle_keyboard_event(&mut self, dispatch: Dispatch<AppState>, event: KeyboardEvent) {
-        let canvas_event = CanvasEvent::KeyPress(event.key());
+        let modifiers = Modifiers {
+            ctrl: event.ctrl_key() || event.meta_key(),
+            shift: event.shift_key(),
+            alt: event.alt_key(),
+        };
+        // alt changes the typed character on some platforms, use the key position instead
+        let key = match event.code().strip_prefix("Key") {
+            Some(letter) if modifiers.alt => letter.to_string(),
+            _ => event.key(),
+        };
+        let chord = KeyChord::new(&key, modifiers);
+        let canvas_event = CanvasEvent::KeyPress(key, modifiers);
+        let library = Dispatch::<Library>::new(dispatch.context()).get();
         dispatch.reduce_mut(|app| {
             if self.tool.handle_event(&canvas_event, &mut self.shape, app) {
                 event.prevent_default();
+            } else if let Some(command) = library.get_keymap().command(&chord) {
+                app.run_command(command);
+                event.prevent_default();
             }
         });
         self.event = Some(canvas_event);
@@ -114,25 +178,30 @@ impl EventHandler {
     pub fn handle_ptr_event(&mut self, dispatch: Dispatch<AppState>, event: PointerEvent) {
         let canvas = self.get_canvas();
         let position = Self::get_event_canvas_postion(&canvas, &event);
+        let modifiers = Self::get_event_modifiers(&event);
         let canvas_event = match event.type_().as_str() {
+            // only primary button drives the tools, secondary opens context menu
+            "pointerdown" if event.button() != 0 => return,
             "pointerdown" => {
                 canvas.set_pointer_capture(event.pointer_id()).unwrap();
-                Some(CanvasEvent::PointerEventStart(position))
+                Some(CanvasEvent::PointerEventStart(position, modifiers))
             }
             "pointerup" => {
                 canvas.release_pointer_capture(event.pointer_id()).unwrap();
                 match self.event {
-                    Some(CanvasEvent::PointerEventStart(_)) => Some(CanvasEvent::Click(position)),
-                    Some(CanvasEvent::DragMove((start, _))) => {
-                        Some(CanvasEvent::DragEnd((start, position)))
+                    Some(CanvasEvent::PointerEventStart(..)) => {
+                        Some(CanvasEvent::Click(position, modifiers))
+                    }
+                    Some(CanvasEvent::DragMove((start, _), _)) => {
+                        Some(CanvasEvent::DragEnd((start, position), modifiers))
                     }
                     _ => None,
                 }
             }
             "pointermove" => match self.event {
-                Some(CanvasEvent::PointerEventStart(start))
-                | Some(CanvasEvent::DragMove((start, _))) => {
-                    Some(CanvasEvent::DragMove((start, position)))
+                Some(CanvasEvent::PointerEventStart(start, _))
+                | Some(CanvasEvent::DragMove((start, _), _)) => {
+                    Some(CanvasEvent::DragMove((start, position), modifiers))
                 }
                 _ => Some(CanvasEvent::Hover(position)),
             },
@@ -148,6 +217,40 @@ impl EventHandler {
         self.event = canvas_event;
     }
 
+    pub fn handle_double_click(&mut self, dispatch: Dispatch<AppState>, event: MouseEvent) {
+        let position = Self::get_event_canvas_postion(&self.get_canvas(), &event);
+        let canvas_event = CanvasEvent::DoubleClick(position);
+        dispatch.reduce_mut(|app| {
+            if self.tool.handle_event(&canvas_event, &mut self.shape, app) {
+                event.prevent_default();
+            }
+        });
+    }
+
+    /// Inserts the image files dropped on the canvas where they were dropped.
+    pub fn handle_drop(&mut self, dispatch: Dispatch<AppState>, event: DragEvent) {
+        event.prevent_default();
+        let Some(files) = event.data_transfer().and_then(|data| data.files()) else {
+            return;
+        };
+        let position = Self::get_event_canvas_postion(&self.get_canvas(), &event);
+        images::insert_files(&files, position, &dispatch);
+    }
+
+    /// Inserts the pasted image files in the middle of the canvas.
+    pub fn handle_paste(&mut self, dispatch: Dispatch<AppState>, event: Event) {
+        let Some(files) = event
+            .dyn_ref::<ClipboardEvent>()
+            .and_then(|event| event.clipboard_data())
+            .and_then(|data| data.files())
+            .filter(|files| files.length() > 0)
+        else {
+            return;
+        };
+        event.prevent_default();
+        images::insert_files(&files, images::viewport().center(), &dispatch);
+    }
+
     fn get_canvas(&self) -> HtmlCanvasElement {
         self.canvas_ref.cast::<HtmlCanvasElement>().unwrap()
     }
@@ -163,15 +266,21 @@ pub fn the_canvas() -> Html {
         |app: &AppState| app.get_shapes().clone(),
         |old, new| old.version.eq(&new.version),
     );
+    let grid = use_selector(|app: &AppState| app.get_grid().clone());
     let current_tool = use_selector(|app: &AppState| app.get_tool().clone());
     let current_ptr = use_selector(|app: &AppState| app.get_pointer().to_owned());
 
     let dispatch = use_dispatch::<AppState>();
+    let context_menu = use_state(|| None::<(i32, i32)>);
 
     let on_pointer_event = {
         let event_handler = event_handler.clone();
         let dispatch = dispatch.clone();
+        let context_menu = context_menu.clone();
         Callback::from(move |event: PointerEvent| {
+            if event.type_() == "pointerdown" && context_menu.is_some() {
+                context_menu.set(None);
+            }
             // Focus canvas on mouse events; required with tabindex for key events to work
             if let Some(canvas) = canvas_ref.cast::<HtmlCanvasElement>() {
                 let _ = canvas.focus();
@@ -183,9 +292,10 @@ pub fn the_canvas() -> Html {
     };
     let onresize = {
         let shapes = shapes.clone();
+        let grid = grid.clone();
         let event_handler = event_handler.clone();
         Callback::from(move |_| {
-            event_handler.borrow_mut().refresh_canvas(&shapes);
+            event_handler.borrow_mut().refresh_canvas(&shapes, &grid);
         })
     };
 
@@ -199,10 +309,56 @@ pub fn the_canvas() -> Html {
         })
     };
 
+    let on_double_click = {
+        let event_handler = event_handler.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |event: MouseEvent| {
+            event_handler
+                .borrow_mut()
+                .handle_double_click(dispatch.clone(), event);
+        })
+    };
+
+    let on_drop = {
+        let event_handler = event_handler.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |event: DragEvent| {
+            event_handler
+                .borrow_mut()
+                .handle_drop(dispatch.clone(), event);
+        })
+    };
+    // dropping is only allowed when dragging over is cancelled
+    let on_drag_over = Callback::from(|event: DragEvent| event.prevent_default());
+
+    let on_paste = {
+        let event_handler = event_handler.clone();
+        let dispatch = dispatch.clone();
+        Callback::from(move |event: Event| {
+            event_handler
+                .borrow_mut()
+                .handle_paste(dispatch.clone(), event);
+        })
+    };
+
+    let on_context_menu = {
+        let context_menu = context_menu.clone();
+        Callback::from(move |event: MouseEvent| {
+            event.prevent_default();
+            context_menu.set(Some((event.client_x(), event.client_y())));
+        })
+    };
+    let on_context_menu_close = {
+        let context_menu = context_menu.clone();
+        Callback::from(move |_| context_menu.set(None))
+    };
+
     {
         let event_handler = event_handler.clone();
-        use_effect_with(shapes.clone(), move |_| {
-            event_handler.borrow().refresh_canvas(&shapes);
+        let dispatch = dispatch.clone();
+        use_effect_with((shapes.clone(), grid.clone()), move |(shapes, grid)| {
+            images::decode(&shapes.images, &dispatch);
+            event_handler.borrow().refresh_canvas(shapes, grid);
         });
     };
     {
@@ -213,16 +369,48 @@ pub fn the_canvas() -> Html {
         });
     };
 
+    // floating alignment and ordering bar above the selection
+    let action_bar = {
+        let items = shapes.selected_units().len();
+        let offset = event_handler
+            .borrow()
+            .canvas_ref
+            .cast::<HtmlCanvasElement>()
+            .map(|canvas| canvas.get_bounding_client_rect())
+            .map_or((0.0, 0.0), |rect| (rect.left(), rect.top()));
+        shapes
+            .bbox_of(&shapes.selected_shapes)
+            .filter(|_| items > 0)
+            .map(|bbox| {
+                let position = (
+                    offset.0 + bbox.left,
+                    offset.1 + (bbox.top - 2.0 * PADDING).max(0.0),
+                );
+                html! { <ActionBar {position} {items} /> }
+            })
+    };
+
     html! {
+        <>
         <canvas
             style={format!("flex: 1; cursor: {current_ptr}; touch-action: none;")}
             ref={event_handler.borrow().canvas_ref.clone()}
             tabindex="0"
             onkeydown={on_key_down}
+            ondblclick={on_double_click}
+            ondrop={on_drop}
+            ondragover={on_drag_over}
+            onpaste={on_paste}
+            oncontextmenu={on_context_menu}
             onpointerup={on_pointer_event.clone()}
             onpointerdown={on_pointer_event.clone()}
             onpointermove={on_pointer_event.clone()}
             {onresize}
         />
+        {for action_bar}
+        if let Some(position) = *context_menu {
+            <ContextMenu {position} onclose={on_context_menu_close} />
+        }
+        </>
     }
 }
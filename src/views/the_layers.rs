@@ -0,0 +1,145 @@
+use crate::components::base_button::BaseButton;
+use crate::store::AppState;
+use crate::store::shapes::Shapes;
+use crate::types::ids::Id;
+use crate::types::shapes::Shape;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+/// Row of the layers panel, a shape or a group listed above its topmost shape.
+struct Layer {
+    id: Id,
+    icon: &'static str,
+    name: String,
+    /// number of groups the row is nested in
+    depth: usize,
+}
+
+impl Layer {
+    /// Rows in reverse draw order, each group before its members.
+    fn rows(shapes: &Shapes) -> Vec<Self> {
+        let mut rows: Vec<Self> = vec![];
+        for shape in shapes.shapes.iter().rev() {
+            let mut ancestors = vec![];
+            let mut current = shape.get_id();
+            while let Some(parent) = shapes.parent(current) {
+                ancestors.push(parent.clone());
+                current = parent;
+            }
+            let depth = ancestors.len();
+            for (index, group) in ancestors.into_iter().rev().enumerate() {
+                if !rows.iter().any(|row| row.id.eq(&group)) {
+                    rows.push(Self {
+                        id: group,
+                        icon: "ti-box-multiple",
+                        name: "Group".to_string(),
+                        depth: index,
+                    });
+                }
+            }
+            rows.push(Self {
+                id: shape.get_id().clone(),
+                icon: shape.get_shape_type().button_icon(),
+                name: shape.get_shape_type().to_string(),
+                depth,
+            });
+        }
+        rows
+    }
+}
+
+#[function_component(TheLayers)]
+pub fn the_layers() -> Html {
+    let dispatch = use_dispatch::<AppState>();
+    let shapes = use_selector_eq(
+        |app: &AppState| app.get_shapes().clone(),
+        |old, new| old.version.eq(&new.version),
+    );
+    let dragged = use_state(|| None::<Id>);
+    html! {
+        <div style=r#"
+            position: absolute;
+            right: 0;
+            top: 10%;
+            max-height: 80%;
+            overflow-y: auto;
+            display: flex;
+            flex-direction: column;
+            background-color: white;
+            box-shadow: -7px 0px 14px 0px rgb(142, 142, 142);
+        "#>
+            <i style="margin: 1px auto;">{"Layers"}</i>
+            {{
+                Layer::rows(&shapes).into_iter().map(|layer| {
+                    let id = layer.id.clone();
+                    let leaves = shapes.leaves(&id);
+                    let members = || {
+                        shapes.shapes.iter().filter(|shape| leaves.contains(shape.get_id()))
+                    };
+                    let selected = leaves.iter().all(|leaf| shapes.selected_shapes.contains(leaf));
+                    let hidden = members().all(Shape::is_hidden);
+                    let locked = members().all(Shape::is_locked);
+                    let style = format!(
+                        "display: flex; align-items: center; cursor: grab; padding-left: {}px; background-color: {};",
+                        15 * layer.depth,
+                        if selected { "lightblue" } else { "white" },
+                    );
+                    html!{
+                        <div
+                            {style}
+                            draggable="true"
+                            onclick={
+                                let id = id.clone();
+                                dispatch.reduce_mut_callback_with(move |app, _| {
+                                    let leaves = app.get_shapes().leaves(&id);
+                                    app.replace_selected(leaves);
+                                })
+                            }
+                            ondragstart={
+                                let dragged = dragged.clone();
+                                let id = id.clone();
+                                Callback::from(move |_: DragEvent| dragged.set(Some(id.clone())))
+                            }
+                            ondragover={Callback::from(|event: DragEvent| event.prevent_default())}
+                            ondrop={
+                                let dragged = dragged.clone();
+                                let id = id.clone();
+                                dispatch.reduce_mut_callback_with(move |app, event: DragEvent| {
+                                    event.prevent_default();
+                                    if let Some(dragged) = &*dragged {
+                                        app.move_shape(dragged, &id);
+                                    }
+                                })
+                            }
+                        >
+                            <i class={classes!("ti", layer.icon)} />
+                            <span style="flex: 1; margin: 0 5px;">{layer.name}</span>
+                            <BaseButton
+                                title={if hidden { "Show" } else { "Hide" }}
+                                onclick={
+                                    let id = id.clone();
+                                    dispatch.reduce_mut_callback_with(move |app, event: MouseEvent| {
+                                        event.stop_propagation();
+                                        app.modify_unit(&id, |x: &mut Shape| x.set_hidden(!hidden));
+                                    })
+                                }>
+                                <i class={classes!("ti", if hidden { "ti-eye-off" } else { "ti-eye" })} />
+                            </BaseButton>
+                            <BaseButton
+                                title={if locked { "Unlock" } else { "Lock" }}
+                                onclick={
+                                    let id = id.clone();
+                                    dispatch.reduce_mut_callback_with(move |app, event: MouseEvent| {
+                                        event.stop_propagation();
+                                        app.modify_unit(&id, |x: &mut Shape| x.set_locked(!locked));
+                                    })
+                                }>
+                                <i class={classes!("ti", if locked { "ti-lock" } else { "ti-lock-open" })} />
+                            </BaseButton>
+                        </div>
+                    }
+                }).collect::<Html>()
+            }}
+        </div>
+    }
+}
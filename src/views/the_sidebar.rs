@@ -1,11 +1,34 @@
+use crate::components::base_button::BaseButton;
 use crate::components::bg_color_button::BackgroundColorButton;
 use crate::components::color_button::ColorButton;
+use crate::store::AppState;
 use crate::types::colors::{BackgroundColor, Color};
+use crate::types::connectors::Routing;
+use crate::types::fills::{FillPattern, FillStyle};
+use crate::types::gradients::Gradient;
+use crate::types::grid::{Grid, GridStyle};
+use crate::types::sketch::RenderStyle;
+use crate::types::strokes::{Dash, StrokeWidth};
+use crate::types::tools::erase_tool::EraseOptions;
+use crate::types::tools::shape_tool::{CornerOptions, PolygonOptions};
 use strum::IntoEnumIterator;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
+use yewdux::prelude::*;
 
 #[function_component(TheSidebar)]
 pub fn the_sidebar() -> Html {
+    let dispatch = use_dispatch::<AppState>();
+    let grid = use_selector(|app: &AppState| app.get_grid().clone());
+    let erase_options = use_selector(|app: &AppState| app.get_erase_options().clone());
+    let polygon_options = use_selector(|app: &AppState| app.get_polygon_options().clone());
+    let corner_options = use_selector(|app: &AppState| app.get_corner_options().clone());
+    let connector_options = use_selector(|app: &AppState| app.get_connector_options().clone());
+    let render_style = use_selector(|app: &AppState| app.get_render_style());
+    let stroke_width = use_selector(|app: &AppState| app.get_stroke_width());
+    let dash = use_selector(|app: &AppState| app.get_dash());
+    let fill_style = use_selector(|app: &AppState| app.get_fill_style().clone());
+    let effects = use_selector(|app: &AppState| app.get_effects().clone());
     html! {
         <div style=r#"
             position: absolute;
@@ -25,6 +48,31 @@ pub fn the_sidebar() -> Html {
                  }).collect::<Html>()
             }}
             </div>
+            <div style="display: flex; align-items: center;">
+            {{
+                StrokeWidth::iter().map(|width| html!{
+                    <BaseButton
+                        selected={width.eq(&stroke_width)}
+                        title={width.to_string()}
+                        onclick={dispatch.reduce_mut_callback(move |app| app.set_stroke_width(width))}>
+                        <span style={format!(
+                            "display: inline-block; width: 14px; vertical-align: middle; border-top: {}px solid black;",
+                            width.width(),
+                        )} />
+                    </BaseButton>
+                }).collect::<Html>()
+            }}
+            {{
+                Dash::iter().map(|pattern| html!{
+                    <BaseButton
+                        selected={pattern.eq(&dash)}
+                        title={pattern.to_string()}
+                        onclick={dispatch.reduce_mut_callback(move |app| app.set_dash(pattern))}>
+                        <i class={classes!("ti", pattern.button_icon())} />
+                    </BaseButton>
+                }).collect::<Html>()
+            }}
+            </div>
             <i style="margin: 1px auto;">{"Fill"}</i>
             <div>
             <BackgroundColorButton bg_color={None} icons={"ti-square"} />
@@ -34,6 +82,401 @@ pub fn the_sidebar() -> Html {
                  }).collect::<Html>()
              }}
             </div>
+            <div style="display: flex; align-items: center;">
+            {{
+                FillPattern::iter().map(|pattern| html!{
+                    <BaseButton
+                        selected={pattern.eq(&fill_style.pattern)}
+                        title={pattern.to_string()}
+                        onclick={dispatch.reduce_mut_callback(move |app| {
+                            app.modify_fill_style(|style| style.pattern = pattern);
+                        })}>
+                        <i class={classes!("ti", pattern.button_icon())} />
+                    </BaseButton>
+                }).collect::<Html>()
+            }}
+            </div>
+            <div style="display: flex; align-items: center;">
+                <i class={classes!("ti", "ti-spacing-vertical")} />
+                <input
+                    type="number"
+                    title="Pattern spacing"
+                    style="width: 3em;"
+                    min={FillStyle::MIN_SPACING.to_string()}
+                    value={fill_style.spacing.to_string()}
+                    onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(spacing) = input.value().parse::<u32>() {
+                            app.modify_fill_style(|style| {
+                                style.spacing = spacing.max(FillStyle::MIN_SPACING)
+                            });
+                        }
+                    })}
+                />
+                <i class={classes!("ti", "ti-angle")} />
+                <input
+                    type="number"
+                    title="Pattern angle (degrees)"
+                    style="width: 3em;"
+                    min="0"
+                    max={(FillStyle::MAX_ANGLE - 1).to_string()}
+                    value={fill_style.angle.to_string()}
+                    onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(angle) = input.value().parse::<u32>() {
+                            app.modify_fill_style(|style| style.angle = angle % FillStyle::MAX_ANGLE);
+                        }
+                    })}
+                />
+            </div>
+            if fill_style.is_gradient() {
+                <div style="display: flex; align-items: center;">
+                if fill_style.pattern == FillPattern::Radial {
+                    <i class={classes!("ti", "ti-radius-bottom-right")} />
+                    <input
+                        type="number"
+                        title="Gradient radius (percent)"
+                        style="width: 3em;"
+                        min="0"
+                        value={fill_style.gradient.radius.to_string()}
+                        onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                            let input: HtmlInputElement = event.target_unchecked_into();
+                            if let Ok(radius) = input.value().parse::<u32>() {
+                                app.modify_fill_style(|style| style.gradient.radius = radius);
+                            }
+                        })}
+                    />
+                } else {
+                    <i class={classes!("ti", "ti-angle")} />
+                    <input
+                        type="number"
+                        title="Gradient angle (degrees)"
+                        style="width: 3em;"
+                        min="0"
+                        max={(Gradient::MAX_ANGLE - 1).to_string()}
+                        value={fill_style.gradient.angle.to_string()}
+                        onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                            let input: HtmlInputElement = event.target_unchecked_into();
+                            if let Ok(angle) = input.value().parse::<u32>() {
+                                app.modify_fill_style(|style| {
+                                    style.gradient.angle = angle % Gradient::MAX_ANGLE
+                                });
+                            }
+                        })}
+                    />
+                }
+                    <BaseButton
+                        title="Add a gradient stop"
+                        onclick={dispatch.reduce_mut_callback(|app| {
+                            app.modify_fill_style(|style| style.gradient.split());
+                        })}>
+                        <i class={classes!("ti", "ti-plus")} />
+                    </BaseButton>
+                </div>
+            {{
+                fill_style.gradient.stops.iter().enumerate().map(|(index, stop)| html!{
+                    <div style="display: flex; align-items: center;">
+                        <BaseButton
+                            title="Stop color, click for the next one"
+                            onclick={dispatch.reduce_mut_callback(move |app| {
+                                app.modify_fill_style(|style| {
+                                    if let Some(stop) = style.gradient.stops.get_mut(index) {
+                                        stop.color = BackgroundColor::iter()
+                                            .cycle()
+                                            .skip_while(|color| color.ne(&stop.color))
+                                            .nth(1)
+                                            .unwrap_or(stop.color.clone());
+                                    }
+                                });
+                            })}>
+                            <i
+                                style={format!("color: {}", stop.color)}
+                                class={classes!("ti", "ti-square-filled")}
+                            />
+                        </BaseButton>
+                        <input
+                            type="number"
+                            title="Stop offset (percent)"
+                            style="width: 3em;"
+                            min="0"
+                            max={Gradient::MAX_OFFSET.to_string()}
+                            value={stop.offset.to_string()}
+                            onchange={dispatch.reduce_mut_callback_with(move |app, event: Event| {
+                                let input: HtmlInputElement = event.target_unchecked_into();
+                                if let Ok(offset) = input.value().parse::<u32>() {
+                                    app.modify_fill_style(|style| {
+                                        if let Some(stop) = style.gradient.stops.get_mut(index) {
+                                            stop.offset = offset.min(Gradient::MAX_OFFSET);
+                                        }
+                                        style.gradient.sort();
+                                    });
+                                }
+                            })}
+                        />
+                        <BaseButton
+                            title="Remove the stop"
+                            onclick={dispatch.reduce_mut_callback(move |app| {
+                                app.modify_fill_style(|style| style.gradient.remove(index));
+                            })}>
+                            <i class={classes!("ti", "ti-x")} />
+                        </BaseButton>
+                    </div>
+                }).collect::<Html>()
+            }}
+            }
+            <i style="margin: 1px auto;">{"Style"}</i>
+            <div>
+            {{
+                RenderStyle::iter().map(|style| html!{
+                    <BaseButton
+                        selected={style.eq(&render_style)}
+                        title={style.to_string()}
+                        onclick={dispatch.reduce_mut_callback(move |app| app.set_render_style(style))}>
+                        <i class={classes!("ti", style.button_icon())} />
+                    </BaseButton>
+                }).collect::<Html>()
+            }}
+            </div>
+            <i style="margin: 1px auto;">{"Effects"}</i>
+            <div style="display: flex; align-items: center;">
+                <BaseButton
+                    selected={effects.shadow.enabled}
+                    title="Drop shadow"
+                    onclick={dispatch.reduce_mut_callback(|app| {
+                        app.modify_effects(|effects| effects.shadow.enabled = !effects.shadow.enabled);
+                    })}>
+                    <i class={classes!("ti", "ti-shadow")} />
+                </BaseButton>
+                <BaseButton
+                    title="Shadow color, click for the next one"
+                    onclick={dispatch.reduce_mut_callback(|app| {
+                        app.modify_effects(|effects| {
+                            let color = &mut effects.shadow.color;
+                            *color = Color::iter()
+                                .cycle()
+                                .skip_while(|other| other.ne(color))
+                                .nth(1)
+                                .unwrap_or_default();
+                        });
+                    })}>
+                    <i
+                        style={format!("color: {}", effects.shadow.color)}
+                        class={classes!("ti", "ti-square-filled")}
+                    />
+                </BaseButton>
+                <i class={classes!("ti", "ti-blur")} />
+                <input
+                    type="number"
+                    title="Blur of the whole shape"
+                    style="width: 3em;"
+                    min="0"
+                    value={effects.blur.to_string()}
+                    onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(blur) = input.value().parse::<u32>() {
+                            app.modify_effects(|effects| effects.blur = blur);
+                        }
+                    })}
+                />
+            </div>
+            if effects.shadow.enabled {
+                <div style="display: flex; align-items: center;">
+                    <i class={classes!("ti", "ti-arrows-move")} />
+                    <input
+                        type="number"
+                        title="Shadow offset right"
+                        style="width: 3em;"
+                        value={effects.shadow.offset_x.to_string()}
+                        onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                            let input: HtmlInputElement = event.target_unchecked_into();
+                            if let Ok(offset) = input.value().parse::<i32>() {
+                                app.modify_effects(|effects| effects.shadow.offset_x = offset);
+                            }
+                        })}
+                    />
+                    <input
+                        type="number"
+                        title="Shadow offset down"
+                        style="width: 3em;"
+                        value={effects.shadow.offset_y.to_string()}
+                        onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                            let input: HtmlInputElement = event.target_unchecked_into();
+                            if let Ok(offset) = input.value().parse::<i32>() {
+                                app.modify_effects(|effects| effects.shadow.offset_y = offset);
+                            }
+                        })}
+                    />
+                    <i class={classes!("ti", "ti-blur")} />
+                    <input
+                        type="number"
+                        title="Shadow blur"
+                        style="width: 3em;"
+                        min="0"
+                        value={effects.shadow.blur.to_string()}
+                        onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                            let input: HtmlInputElement = event.target_unchecked_into();
+                            if let Ok(blur) = input.value().parse::<u32>() {
+                                app.modify_effects(|effects| effects.shadow.blur = blur);
+                            }
+                        })}
+                    />
+                </div>
+            }
+            <i style="margin: 1px auto;">{"Grid"}</i>
+            <div style="display: flex; align-items: center;">
+            {{
+                GridStyle::iter().map(|style| html!{
+                    <BaseButton
+                        selected={style.eq(&grid.style)}
+                        title={style.to_string()}
+                        onclick={dispatch.reduce_mut_callback(move |app| {
+                            app.modify_grid(|grid| grid.style = style);
+                        })}>
+                        <i class={classes!("ti", style.button_icon())} />
+                    </BaseButton>
+                }).collect::<Html>()
+            }}
+                <input
+                    type="number"
+                    title="Grid spacing"
+                    style="width: 4em;"
+                    min={Grid::MIN_SPACING.to_string()}
+                    value={grid.spacing.to_string()}
+                    onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(spacing) = input.value().parse::<u32>() {
+                            app.modify_grid(|grid| grid.spacing = spacing.max(Grid::MIN_SPACING));
+                        }
+                    })}
+                />
+            </div>
+            <i style="margin: 1px auto;">{"Corners"}</i>
+            <div style="display: flex; align-items: center;">
+                <i class={classes!("ti", "ti-border-radius")} />
+                <input
+                    type="number"
+                    title="Corner radius"
+                    style="width: 4em;"
+                    min="0"
+                    max={corner_options.proportional.then_some(CornerOptions::MAX_PERCENT.to_string())}
+                    value={corner_options.radius.to_string()}
+                    onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(radius) = input.value().parse::<u32>() {
+                            app.modify_corner_options(|options| options.radius = radius);
+                        }
+                    })}
+                />
+                <BaseButton
+                    selected={corner_options.proportional}
+                    title="Radius in percent of the shorter side"
+                    onclick={dispatch.reduce_mut_callback(|app| {
+                        app.modify_corner_options(|options| {
+                            options.proportional = !options.proportional
+                        });
+                    })}>
+                    <i class={classes!("ti", "ti-percentage")} />
+                </BaseButton>
+            </div>
+            <i style="margin: 1px auto;">{"Polygon"}</i>
+            <div style="display: flex; align-items: center;">
+                <i class={classes!("ti", "ti-hexagon")} />
+                <input
+                    type="number"
+                    title="Polygon sides"
+                    style="width: 3em;"
+                    min={PolygonOptions::MIN_VERTICES.to_string()}
+                    max={PolygonOptions::MAX_VERTICES.to_string()}
+                    value={polygon_options.sides.to_string()}
+                    onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(sides) = input.value().parse::<u32>() {
+                            app.modify_polygon_options(|options| {
+                                options.sides = sides
+                                    .clamp(PolygonOptions::MIN_VERTICES, PolygonOptions::MAX_VERTICES)
+                            });
+                        }
+                    })}
+                />
+                <i class={classes!("ti", "ti-star")} />
+                <input
+                    type="number"
+                    title="Star points"
+                    style="width: 3em;"
+                    min={PolygonOptions::MIN_VERTICES.to_string()}
+                    max={PolygonOptions::MAX_VERTICES.to_string()}
+                    value={polygon_options.points.to_string()}
+                    onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(points) = input.value().parse::<u32>() {
+                            app.modify_polygon_options(|options| {
+                                options.points = points
+                                    .clamp(PolygonOptions::MIN_VERTICES, PolygonOptions::MAX_VERTICES)
+                            });
+                        }
+                    })}
+                />
+                <input
+                    type="number"
+                    title="Star inner radius (percent)"
+                    style="width: 3em;"
+                    min={PolygonOptions::MIN_INNER_PERCENT.to_string()}
+                    max={PolygonOptions::MAX_INNER_PERCENT.to_string()}
+                    value={polygon_options.inner_percent.to_string()}
+                    onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(percent) = input.value().parse::<u32>() {
+                            app.modify_polygon_options(|options| {
+                                options.inner_percent = percent.clamp(
+                                    PolygonOptions::MIN_INNER_PERCENT,
+                                    PolygonOptions::MAX_INNER_PERCENT,
+                                )
+                            });
+                        }
+                    })}
+                />
+            </div>
+            <i style="margin: 1px auto;">{"Connector"}</i>
+            <div>
+            {{
+                Routing::iter().map(|routing| html!{
+                    <BaseButton
+                        selected={routing.eq(&connector_options.routing)}
+                        title={routing.to_string()}
+                        onclick={dispatch.reduce_mut_callback(move |app| {
+                            app.modify_connector_options(|options| options.routing = routing);
+                        })}>
+                        <i class={classes!("ti", routing.button_icon())} />
+                    </BaseButton>
+                }).collect::<Html>()
+            }}
+            </div>
+            <i style="margin: 1px auto;">{"Eraser"}</i>
+            <div style="display: flex; align-items: center;">
+                <BaseButton
+                    selected={erase_options.partial}
+                    title="Erase along the path, cutting freehand strokes."
+                    onclick={dispatch.reduce_mut_callback(|app| {
+                        app.modify_erase_options(|options| options.partial = !options.partial);
+                    })}>
+                    <i class={classes!("ti", "ti-scissors")} />
+                </BaseButton>
+                <input
+                    type="number"
+                    title="Eraser radius"
+                    style="width: 4em;"
+                    min={EraseOptions::MIN_RADIUS.to_string()}
+                    value={erase_options.radius.to_string()}
+                    onchange={dispatch.reduce_mut_callback_with(|app, event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        if let Ok(radius) = input.value().parse::<u32>() {
+                            app.modify_erase_options(|options| {
+                                options.radius = radius.max(EraseOptions::MIN_RADIUS)
+                            });
+                        }
+                    })}
+                />
+            </div>
         </div>
     }
 }
@@ -1,7 +1,11 @@
 use crate::components::base_button::BaseButton;
+use crate::components::keymap_editor::KeymapEditor;
+use crate::components::preset_editor::PresetEditor;
 use crate::store::AppState;
 use crate::types::tools::{Tool, ToolAction};
+use crate::types::{images, svg};
 use strum::IntoEnumIterator;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yewdux::prelude::*;
 
@@ -9,7 +13,47 @@ use yewdux::prelude::*;
 pub fn the_toolbar() -> Html {
     let dispatch = use_dispatch::<AppState>();
     let current_tool = use_selector(|app: &AppState| app.get_tool().clone());
+    let grid = use_selector(|app: &AppState| app.get_grid().clone());
+    let select_options = use_selector(|app: &AppState| app.get_select_options().clone());
+    let show_keymap = use_state(|| false);
+    let show_presets = use_state(|| false);
+    let image_input = use_node_ref();
+    let pick_image = {
+        let image_input = image_input.clone();
+        Callback::from(move |_| {
+            if let Some(input) = image_input.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+    let insert_images = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            if let Some(files) = input.files() {
+                images::insert_files(&files, images::viewport().center(), &dispatch);
+            }
+            // picking the same file again should insert it again
+            input.set_value("");
+        })
+    };
+    let export_svg = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |_| {
+            let document = svg::export(dispatch.get().get_shapes());
+            svg::download(&document, "canvas.svg");
+        })
+    };
+    let toggle_presets = {
+        let show_presets = show_presets.clone();
+        Callback::from(move |_| show_presets.set(!*show_presets))
+    };
+    let toggle_keymap = {
+        let show_keymap = show_keymap.clone();
+        Callback::from(move |_| show_keymap.set(!*show_keymap))
+    };
     html! {
+        <>
         <div style=r#"
             position: absolute;
             left: 50%;
@@ -34,6 +78,66 @@ pub fn the_toolbar() -> Html {
                 </BaseButton>
             }}).collect::<Html>()
         }}
+        <BaseButton
+            title="Insert image (or drop or paste one on the canvas)."
+            onclick={pick_image}>
+            <i class={classes!("ti", "ti-photo")} />
+        </BaseButton>
+        <input
+            type="file"
+            accept={images::ACCEPTED.join(",")}
+            multiple=true
+            style="display: none;"
+            ref={image_input}
+            onchange={insert_images}
+        />
+        <BaseButton title="Export as SVG" onclick={export_svg}>
+            <i class={classes!("ti", "ti-file-export")} />
+        </BaseButton>
+        <BaseButton
+            selected={select_options.lasso}
+            title="Lasso selection (or drag with Alt)."
+            onclick={dispatch.reduce_mut_callback(|app| {
+                app.modify_select_options(|options| options.lasso = !options.lasso);
+            })}>
+            <i class={classes!("ti", "ti-lasso")} />
+        </BaseButton>
+        <BaseButton
+            selected={select_options.intersect}
+            title="Select shapes touching the selection, not only those inside it."
+            onclick={dispatch.reduce_mut_callback(|app| {
+                app.modify_select_options(|options| options.intersect = !options.intersect);
+            })}>
+            <i class={classes!("ti", "ti-box-margin")} />
+        </BaseButton>
+        <BaseButton
+            selected={grid.visible}
+            title="Show grid"
+            onclick={dispatch.reduce_mut_callback(|app| {
+                app.modify_grid(|grid| grid.visible = !grid.visible);
+            })}>
+            <i class={classes!("ti", "ti-layout-grid")} />
+        </BaseButton>
+        <BaseButton
+            selected={grid.snap}
+            title="Snap to grid (hold Alt to disable)."
+            onclick={dispatch.reduce_mut_callback(|app| {
+                app.modify_grid(|grid| grid.snap = !grid.snap);
+            })}>
+            <i class={classes!("ti", "ti-magnet")} />
+        </BaseButton>
+        <BaseButton
+            selected={*show_presets}
+            title="Style presets"
+            onclick={toggle_presets.clone()}>
+            <i class={classes!("ti", "ti-palette")} />
+        </BaseButton>
+        <BaseButton
+            selected={*show_keymap}
+            title="Keyboard shortcuts"
+            onclick={toggle_keymap.clone()}>
+            <i class={classes!("ti", "ti-keyboard")} />
+        </BaseButton>
         <BaseButton
             title="Source Code"
             onclick={move |_| {
@@ -43,5 +147,12 @@ pub fn the_toolbar() -> Html {
             <i class={classes!("ti", "ti-brand-git")} />
         </BaseButton>
         </div>
+        if *show_keymap {
+            <KeymapEditor onclose={toggle_keymap} />
+        }
+        if *show_presets {
+            <PresetEditor onclose={toggle_presets} />
+        }
+        </>
     }
 }